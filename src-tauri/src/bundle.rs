@@ -0,0 +1,387 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::AppConfig;
+use crate::migration::migrate;
+use crate::rules::ProcessRule;
+use crate::storage::write_atomic;
+use crate::validation::IssueSeverity;
+
+const BUNDLE_FORMAT: &str = "langcon-settings";
+
+/// Fields that describe this machine rather than the user's preferences; never exported.
+/// Only the active profile's settings travel in a bundle, so the profile list stays local too.
+const LOCAL_ONLY_FIELDS: [&str; 4] = ["start_with_windows", "schema_version", "active_profile", "profiles"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleScope {
+    Full,
+    ProcessesOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// Adds imported rules and updates rules with the same action, process name, path and
+    /// title pattern; removes nothing. An imported exclude never replaces a local include.
+    /// Settings present in a full bundle overwrite the current values.
+    Merge,
+    /// Replaces the rule list (and, for full bundles, every portable setting) with the bundle's.
+    Replace,
+}
+
+/// Portable settings file. `config` holds an `AppConfig` in the schema named by
+/// `schema_version`, so older bundles go through the same migration chain as `config.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub format: String,
+    pub scope: BundleScope,
+    pub app_version: String,
+    pub exported_at: String,
+    pub config: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleChange {
+    pub before: ProcessRule,
+    pub after: ProcessRule,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub scope: BundleScope,
+    pub mode: ImportMode,
+    pub added_rules: Vec<ProcessRule>,
+    pub removed_rules: Vec<ProcessRule>,
+    pub changed_rules: Vec<RuleChange>,
+    pub changed_settings: Vec<SettingChange>,
+}
+
+impl SettingsBundle {
+    pub fn from_config(config: &AppConfig, scope: BundleScope) -> Result<Self> {
+        let mut value = serde_json::to_value(config).context("config 직렬화 실패")?;
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("config 직렬화 결과가 객체가 아닙니다"))?;
+        match scope {
+            BundleScope::Full => {
+                for field in LOCAL_ONLY_FIELDS {
+                    object.remove(field);
+                }
+            }
+            BundleScope::ProcessesOnly => object.retain(|key, _| key == "process_rules"),
+        }
+        object.insert("schema_version".to_string(), Value::from(config.schema_version));
+
+        Ok(Self {
+            format: BUNDLE_FORMAT.to_string(),
+            scope,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: chrono::Local::now().to_rfc3339(),
+            config: value,
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(self).context("설정 번들 직렬화 실패")?;
+        write_atomic(path, serialized.as_bytes()).context("설정 번들 저장 실패")
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path).context("설정 번들 파일을 읽을 수 없습니다")?;
+        let bundle: Self = serde_json::from_str(&raw).context("설정 번들 형식이 올바르지 않습니다")?;
+        if bundle.format != BUNDLE_FORMAT {
+            return Err(anyhow!("Langcon 설정 번들이 아닙니다: {}", bundle.format));
+        }
+        Ok(bundle)
+    }
+
+    /// Applies the bundle on top of `current` and returns the resulting config.
+    pub fn apply_to(&self, current: &AppConfig, mode: ImportMode) -> Result<AppConfig> {
+        let mut value = self.config.clone();
+        migrate(&mut value)?;
+        let imported_fields: Vec<String> = value
+            .as_object()
+            .map(|object| object.keys().cloned().collect())
+            .unwrap_or_default();
+        let mut imported: AppConfig = serde_json::from_value(value).context("설정 번들의 config를 읽을 수 없습니다")?;
+        // Rejected like the same edit made by hand, rather than dropped or repaired quietly.
+        if let Some(issue) = imported
+            .normalize_with_report()
+            .into_iter()
+            .find(|issue| issue.severity == IssueSeverity::Error)
+        {
+            return Err(anyhow!(
+                "설정 번들에 올바르지 않은 규칙이 있습니다: {} ({})",
+                issue.field,
+                issue.value
+            ));
+        }
+
+        let mut next = current.clone();
+        if self.scope == BundleScope::Full {
+            let mut merged = serde_json::to_value(&next).context("config 직렬화 실패")?;
+            let source = serde_json::to_value(&imported).context("config 직렬화 실패")?;
+            if let (Some(target), Some(source)) = (merged.as_object_mut(), source.as_object()) {
+                for field in imported_fields.iter().filter(|field| {
+                    field.as_str() != "process_rules" && !LOCAL_ONLY_FIELDS.contains(&field.as_str())
+                }) {
+                    if let Some(value) = source.get(field) {
+                        target.insert(field.clone(), value.clone());
+                    }
+                }
+            }
+            next = serde_json::from_value(merged).context("config 병합 실패")?;
+        }
+
+        match mode {
+            ImportMode::Replace => next.process_rules = imported.process_rules,
+            ImportMode::Merge => {
+                for mut rule in imported.process_rules {
+                    match next
+                        .process_rules
+                        .iter_mut()
+                        .find(|existing| same_target(existing, &rule))
+                    {
+                        Some(existing) => {
+                            rule.id = existing.id.clone();
+                            *existing = rule;
+                        }
+                        None => {
+                            rule.id = next.next_rule_id();
+                            next.process_rules.push(rule);
+                        }
+                    }
+                }
+            }
+        }
+
+        next.normalize();
+        Ok(next)
+    }
+
+    pub fn preview(&self, current: &AppConfig, mode: ImportMode) -> Result<ImportPreview> {
+        let next = self.apply_to(current, mode)?;
+        let mut preview = ImportPreview {
+            scope: self.scope,
+            mode,
+            added_rules: Vec::new(),
+            removed_rules: Vec::new(),
+            changed_rules: Vec::new(),
+            changed_settings: Vec::new(),
+        };
+
+        for rule in &next.process_rules {
+            match current
+                .process_rules
+                .iter()
+                .find(|existing| same_target(existing, rule))
+            {
+                None => preview.added_rules.push(rule.clone()),
+                Some(existing) if !same_rule(existing, rule) => preview.changed_rules.push(RuleChange {
+                    before: existing.clone(),
+                    after: rule.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        preview.removed_rules = current
+            .process_rules
+            .iter()
+            .filter(|existing| {
+                !next
+                    .process_rules
+                    .iter()
+                    .any(|rule| same_target(rule, existing))
+            })
+            .cloned()
+            .collect();
+
+        let before = serde_json::to_value(current).context("config 직렬화 실패")?;
+        let after = serde_json::to_value(&next).context("config 직렬화 실패")?;
+        if let (Some(before), Some(after)) = (before.as_object(), after.as_object()) {
+            for (field, value) in after {
+                if field == "process_rules" {
+                    continue;
+                }
+                let previous = before.get(field).cloned().unwrap_or(Value::Null);
+                if previous != *value {
+                    preview.changed_settings.push(SettingChange {
+                        field: field.clone(),
+                        before: previous,
+                        after: value.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(preview)
+    }
+}
+
+/// Rules are matched across configs by what they target and do, since ids are assigned
+/// locally. Names compare like matching does, ignoring case.
+fn same_target(a: &ProcessRule, b: &ProcessRule) -> bool {
+    a.action == b.action
+        && a.process_name.eq_ignore_ascii_case(&b.process_name)
+        && a.path_pattern == b.path_pattern
+        && a.title_pattern == b.title_pattern
+}
+
+/// Rule ids are local bookkeeping, so two rules that differ only by id are the same rule.
+fn same_rule(a: &ProcessRule, b: &ProcessRule) -> bool {
+    ProcessRule {
+        id: String::new(),
+        ..a.clone()
+    } == ProcessRule {
+        id: String::new(),
+        ..b.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{RuleAction, TargetInputMode, TitleMatchKind};
+
+    fn rule(id: &str, name: &str, action: RuleAction) -> ProcessRule {
+        ProcessRule {
+            id: id.into(),
+            process_name: name.into(),
+            action,
+            ..ProcessRule::default()
+        }
+    }
+
+    fn config(rules: Vec<ProcessRule>) -> AppConfig {
+        AppConfig {
+            process_rules: rules,
+            ..AppConfig::default()
+        }
+    }
+
+    fn bundle(rules: Vec<ProcessRule>, scope: BundleScope) -> SettingsBundle {
+        SettingsBundle::from_config(&config(rules), scope).unwrap()
+    }
+
+    #[test]
+    fn merged_exclude_does_not_replace_a_local_include() {
+        let current = config(vec![rule("rule-1", "foo.exe", RuleAction::Include)]);
+        let bundle = bundle(vec![rule("rule-9", "Foo.exe", RuleAction::Exclude)], BundleScope::ProcessesOnly);
+
+        let next = bundle.apply_to(&current, ImportMode::Merge).unwrap();
+
+        assert_eq!(next.process_rules.len(), 2);
+        assert_eq!(next.process_rules[0], current.process_rules[0]);
+        assert_eq!(next.process_rules[1].action, RuleAction::Exclude);
+    }
+
+    #[test]
+    fn merge_updates_the_same_rule_ignoring_case() {
+        let current = config(vec![rule("rule-1", "javaw.exe", RuleAction::Include)]);
+        let imported = ProcessRule {
+            target_mode: TargetInputMode::Original,
+            ..rule("rule-9", "JavaW.exe", RuleAction::Include)
+        };
+        let bundle = bundle(vec![imported], BundleScope::ProcessesOnly);
+
+        let next = bundle.apply_to(&current, ImportMode::Merge).unwrap();
+
+        assert_eq!(next.process_rules.len(), 1);
+        assert_eq!(next.process_rules[0].id, "rule-1");
+        assert_eq!(next.process_rules[0].target_mode, TargetInputMode::Original);
+    }
+
+    #[test]
+    fn replace_swaps_the_rule_list() {
+        let current = config(vec![rule("rule-1", "a.exe", RuleAction::Include)]);
+        let bundle = bundle(vec![rule("rule-1", "b.exe", RuleAction::Include)], BundleScope::ProcessesOnly);
+
+        let next = bundle.apply_to(&current, ImportMode::Replace).unwrap();
+
+        let names: Vec<&str> = next.process_rules.iter().map(|rule| rule.process_name.as_str()).collect();
+        assert_eq!(names, ["b.exe"]);
+    }
+
+    #[test]
+    fn preview_describes_what_apply_does() {
+        let current = config(vec![
+            rule("rule-1", "a.exe", RuleAction::Include),
+            rule("rule-2", "b.exe", RuleAction::Include),
+        ]);
+        let changed = ProcessRule {
+            target_mode: TargetInputMode::Original,
+            ..rule("rule-7", "b.exe", RuleAction::Include)
+        };
+        let bundle = bundle(
+            vec![changed, rule("rule-8", "c.exe", RuleAction::Exclude)],
+            BundleScope::ProcessesOnly,
+        );
+
+        for mode in [ImportMode::Merge, ImportMode::Replace] {
+            let next = bundle.apply_to(&current, mode).unwrap();
+            let preview = bundle.preview(&current, mode).unwrap();
+
+            let in_next = |rule: &ProcessRule| next.process_rules.iter().any(|next| next == rule);
+            assert!(preview.added_rules.iter().all(in_next));
+            assert!(preview.changed_rules.iter().all(|change| in_next(&change.after)));
+            assert!(!preview.removed_rules.iter().any(in_next));
+            let unchanged = current.process_rules.iter().filter(|rule| in_next(rule)).count();
+            let described = preview.added_rules.len() + preview.changed_rules.len() + unchanged;
+            assert_eq!(described, next.process_rules.len(), "{mode:?}");
+        }
+        let replaced = bundle.preview(&current, ImportMode::Replace).unwrap();
+        assert_eq!(replaced.removed_rules, [current.process_rules[0].clone()]);
+    }
+
+    #[test]
+    fn local_only_fields_stay_local() {
+        let mut local = config(Vec::new());
+        local.start_with_windows = true;
+        let exported = SettingsBundle::from_config(&local, BundleScope::Full).unwrap();
+        let object = exported.config.as_object().unwrap();
+        for field in LOCAL_ONLY_FIELDS.iter().filter(|field| **field != "schema_version") {
+            assert!(!object.contains_key(*field), "{field}");
+        }
+
+        let mut incoming = exported.clone();
+        let object = incoming.config.as_object_mut().unwrap();
+        object.insert("start_with_windows".into(), Value::Bool(true));
+        object.insert("active_profile".into(), Value::from("Imported"));
+        let current = config(Vec::new());
+
+        let next = incoming.apply_to(&current, ImportMode::Replace).unwrap();
+
+        assert!(!next.start_with_windows);
+        assert_eq!(next.active_profile, current.active_profile);
+        assert_eq!(next.profiles, current.profiles);
+    }
+
+    #[test]
+    fn rules_with_invalid_patterns_are_rejected() {
+        let broken = ProcessRule {
+            title_pattern: Some("(".into()),
+            title_match_kind: TitleMatchKind::Regex,
+            ..rule("rule-1", "a.exe", RuleAction::Include)
+        };
+        let bundle = bundle(vec![broken], BundleScope::ProcessesOnly);
+
+        assert!(bundle.apply_to(&config(Vec::new()), ImportMode::Merge).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use crate::config::{ConfigManager, determine_config_dir};
+use crate::process::ProcessInfo;
+use crate::resolver::RuleSet;
+use crate::trace::replay;
+use crate::validation::has_errors;
+
+pub const CHECK_CONFIG_FLAG: &str = "--check-config";
+pub const TEST_RULE_FLAG: &str = "--test-rule";
+pub const REPLAY_TRACE_FLAG: &str = "--replay-trace";
+const NAME_FLAG: &str = "--name";
+const PATH_FLAG: &str = "--path";
+const TITLE_FLAG: &str = "--title";
+
+/// Runs a one-shot command-line mode if one was requested and returns its exit code.
+/// Returns `None` when the app should start normally.
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == CHECK_CONFIG_FLAG) {
+        attach_parent_console();
+        return Some(check_config());
+    }
+    if args.iter().any(|arg| arg == TEST_RULE_FLAG) {
+        attach_parent_console();
+        return Some(test_rule(&args));
+    }
+    if args.iter().any(|arg| arg.split('=').next() == Some(REPLAY_TRACE_FLAG)) {
+        attach_parent_console();
+        return Some(replay_trace(&args));
+    }
+    None
+}
+
+fn check_config() -> i32 {
+    let location = match determine_config_dir() {
+        Ok(location) => location,
+        Err(err) => {
+            eprintln!("config 디렉터리를 확인할 수 없습니다: {err:#}");
+            return 2;
+        }
+    };
+
+    println!("config: {}", location.dir.display());
+    let issues = ConfigManager::check(&location);
+    if issues.is_empty() {
+        println!("문제가 없습니다.");
+        return 0;
+    }
+
+    for issue in &issues {
+        println!("{}", issue.describe());
+    }
+    if has_errors(&issues) { 1 } else { 0 }
+}
+
+/// Prints how the saved rules treat a window described by `--name`, `--path` and `--title`.
+/// Exits with 0 when the window would be switched and 1 when it would be left alone.
+fn test_rule(args: &[String]) -> i32 {
+    let name = flag_value(args, NAME_FLAG).unwrap_or_default();
+    let path = flag_value(args, PATH_FLAG);
+    let title = flag_value(args, TITLE_FLAG);
+    if name.trim().is_empty() && path.is_none() && title.is_none() {
+        eprintln!("{NAME_FLAG}, {PATH_FLAG}, {TITLE_FLAG} 중 하나 이상을 지정해야 합니다.");
+        return 2;
+    }
+
+    let config = match determine_config_dir().and_then(|location| ConfigManager::read_only(&location)) {
+        Ok((config, _)) => config,
+        Err(err) => {
+            eprintln!("config를 읽을 수 없습니다: {err:#}");
+            return 2;
+        }
+    };
+
+    let process = ProcessInfo::described(&name, path.as_deref(), title.as_deref());
+    let result = RuleSet::compile(&config).test(&process, config.use_auto_to_en);
+    match serde_json::to_string_pretty(&result) {
+        Ok(json) => println!("{json}"),
+        Err(err) => {
+            eprintln!("결과를 직렬화할 수 없습니다: {err}");
+            return 2;
+        }
+    }
+    if result.action.is_some() { 0 } else { 1 }
+}
+
+/// Replays a trace recorded by the app through the current engine and prints where its
+/// decisions differ. Exits with 0 when they all match and 1 when some do not.
+fn replay_trace(args: &[String]) -> i32 {
+    let Some(path) = flag_value(args, REPLAY_TRACE_FLAG) else {
+        eprintln!("{REPLAY_TRACE_FLAG} 다음에 trace 파일 경로를 지정해야 합니다.");
+        return 2;
+    };
+    let report = match File::open(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|file| replay(BufReader::new(file)))
+    {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("trace를 재생할 수 없습니다: {err:#}");
+            return 2;
+        }
+    };
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{json}"),
+        Err(err) => {
+            eprintln!("결과를 직렬화할 수 없습니다: {err}");
+            return 2;
+        }
+    }
+    if report.mismatches.is_empty() { 0 } else { 1 }
+}
+
+/// Accepts both `--flag value` and `--flag=value`.
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Release builds use the GUI subsystem and have no console of their own, so borrow the
+/// console of the shell that launched us to make `println!` visible.
+fn attach_parent_console() {
+    #[cfg(windows)]
+    unsafe {
+        use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
@@ -1,18 +1,52 @@
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
 
+use crate::migration::{CONFIG_SCHEMA_VERSION, MigrationOutcome, backup_before_migration, migrate};
+use crate::process::DEFAULT_IGNORED_PROCESSES;
+use crate::profiles::{DEFAULT_PROFILE_NAME, StoredProfile};
+use crate::recent::RecentApps;
+use crate::resolver::invalid_rule_patterns;
+use crate::rules::{ProcessRule, ResumePolicy};
+use crate::storage::{RollingBackups, quarantine, read_to_string_retrying, write_atomic};
+use crate::validation::{ConfigIssue, IssueKind, is_positive_finite};
+
 const CONFIG_FILE_NAME: &str = "config.json";
 const WINDOW_STATE_FILE_NAME: &str = "window.json";
+const RECENT_APPS_FILE_NAME: &str = "recent.json";
+const TRACE_DIR_NAME: &str = "traces";
+const BACKUP_DIR_NAME: &str = "backups";
+const PORTABLE_MARKER_FILE_NAME: &str = "langcon.portable";
+const PORTABLE_DATA_DIR_NAME: &str = "data";
+pub const CONFIG_DIR_FLAG: &str = "--config-dir";
+pub const CONFIG_DIR_ENV: &str = "LANGCON_CONFIG_DIR";
+const CONFIG_BACKUP_LIMIT: usize = 5;
+const WINDOW_STATE_BACKUP_LIMIT: usize = 2;
+const WINDOW_STATE_BACKUP_INTERVAL: Duration = Duration::from_secs(60);
 pub const FALLBACK_LANGUAGE: &str = "en";
 pub const SUPPORTED_LANGUAGES: [&str; 4] = ["en", "ko", "ja", "zh"];
+pub const DEFAULT_DETECT_INTERVAL_SECS: f32 = 0.5;
+pub const MIN_DETECT_INTERVAL_SECS: f32 = 0.1;
+pub const DEFAULT_MOUSE_SENSITIVITY: f32 = 100.0;
+pub const MIN_MOUSE_SENSITIVITY: f32 = 1.0;
+pub const MIN_PROCESS_AUTO_REFRESH_SECS: f32 = 1.0;
 
 fn default_language() -> String {
     FALLBACK_LANGUAGE.to_string()
 }
 
+pub fn default_ignored_processes() -> Vec<String> {
+    DEFAULT_IGNORED_PROCESSES
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
 pub fn sanitize_language(value: impl AsRef<str>) -> String {
     let lower = value.as_ref().to_lowercase();
     if SUPPORTED_LANGUAGES.iter().any(|lang| *lang == lower) {
@@ -22,100 +56,498 @@ pub fn sanitize_language(value: impl AsRef<str>) -> String {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AppConfig {
-    #[serde(alias = "selected_process_list")]
-    pub selected_processes: Vec<String>,
+    pub schema_version: u32,
+    /// Name of the profile whose settings are in the fields below.
+    pub active_profile: String,
+    pub profiles: Vec<StoredProfile>,
+    pub process_rules: Vec<ProcessRule>,
     #[serde(alias = "use_auto_org_to_en")]
     pub use_auto_to_en: bool,
     #[serde(alias = "use_mouse_move_event")]
     pub use_mouse_move_event: bool,
+    /// Polling interval right after the focus or the input mode changes.
     #[serde(alias = "detect_process_language_interval")]
     pub detect_interval_secs: f32,
+    /// Upper bound the polling interval backs off to while nothing changes. Equal to
+    /// `detect_interval_secs` (the default) polls at a fixed rate.
+    pub max_detect_interval_secs: f32,
     #[serde(alias = "detect_mose_movement_sensitivity")]
     pub mouse_sensitivity: f32,
+    /// Other ways a paused app resumes automatic switching; rules can replace the list.
+    pub resume_policies: Vec<ResumePolicy>,
     #[serde(alias = "in_english")]
     pub in_english: bool,
     #[serde(alias = "start_with_windows")]
     pub start_with_windows: bool,
     #[serde(default = "default_language")]
     pub language: String,
+    /// Wildcard patterns (`*`, `?`) for processes hidden from the process picker.
+    pub ignored_processes: Vec<String>,
+    /// Re-enumerates the process picker this often; `0` turns auto-refresh off.
+    pub process_auto_refresh_secs: f32,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
-            selected_processes: Vec::new(),
+            schema_version: CONFIG_SCHEMA_VERSION,
+            active_profile: DEFAULT_PROFILE_NAME.to_string(),
+            profiles: Vec::new(),
+            process_rules: Vec::new(),
             use_auto_to_en: true,
             use_mouse_move_event: true,
-            detect_interval_secs: 0.5,
-            mouse_sensitivity: 100.0,
+            detect_interval_secs: DEFAULT_DETECT_INTERVAL_SECS,
+            max_detect_interval_secs: DEFAULT_DETECT_INTERVAL_SECS,
+            mouse_sensitivity: DEFAULT_MOUSE_SENSITIVITY,
+            resume_policies: Vec::new(),
             in_english: false,
             start_with_windows: false,
             language: default_language(),
+            ignored_processes: default_ignored_processes(),
+            process_auto_refresh_secs: 0.0,
         }
     }
 }
 
 impl AppConfig {
     pub fn normalize(&mut self) {
-        if self.detect_interval_secs <= 0.0 {
-            self.detect_interval_secs = 0.5;
+        self.normalize_with_report();
+    }
+
+    /// Replaces invalid values with safe ones and reports every replacement.
+    pub fn normalize_with_report(&mut self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        if !is_positive_finite(self.detect_interval_secs) {
+            issues.push(ConfigIssue::new(
+                "detect_interval_secs",
+                IssueKind::NotPositive,
+                self.detect_interval_secs,
+                DEFAULT_DETECT_INTERVAL_SECS,
+            ));
+            self.detect_interval_secs = DEFAULT_DETECT_INTERVAL_SECS;
+        } else if self.detect_interval_secs < MIN_DETECT_INTERVAL_SECS {
+            issues.push(ConfigIssue::new(
+                "detect_interval_secs",
+                IssueKind::BelowMinimum,
+                self.detect_interval_secs,
+                MIN_DETECT_INTERVAL_SECS,
+            ));
+            self.detect_interval_secs = MIN_DETECT_INTERVAL_SECS;
+        }
+
+        if !is_positive_finite(self.max_detect_interval_secs) {
+            issues.push(ConfigIssue::new(
+                "max_detect_interval_secs",
+                IssueKind::NotPositive,
+                self.max_detect_interval_secs,
+                self.detect_interval_secs,
+            ));
+            self.max_detect_interval_secs = self.detect_interval_secs;
+        } else if self.max_detect_interval_secs < self.detect_interval_secs {
+            issues.push(ConfigIssue::new(
+                "max_detect_interval_secs",
+                IssueKind::BelowMinimum,
+                self.max_detect_interval_secs,
+                self.detect_interval_secs,
+            ));
+            self.max_detect_interval_secs = self.detect_interval_secs;
+        }
+
+        if !is_positive_finite(self.mouse_sensitivity) {
+            issues.push(ConfigIssue::new(
+                "mouse_sensitivity",
+                IssueKind::NotPositive,
+                self.mouse_sensitivity,
+                DEFAULT_MOUSE_SENSITIVITY,
+            ));
+            self.mouse_sensitivity = DEFAULT_MOUSE_SENSITIVITY;
+        } else if self.mouse_sensitivity < MIN_MOUSE_SENSITIVITY {
+            issues.push(ConfigIssue::new(
+                "mouse_sensitivity",
+                IssueKind::BelowMinimum,
+                self.mouse_sensitivity,
+                MIN_MOUSE_SENSITIVITY,
+            ));
+            self.mouse_sensitivity = MIN_MOUSE_SENSITIVITY;
+        }
+
+        for policy in self.resume_policies.iter().filter(|policy| !policy.is_valid()) {
+            issues.push(ConfigIssue::new(
+                "resume_policies",
+                IssueKind::NotPositive,
+                serde_json::to_value(policy).unwrap_or_default(),
+                serde_json::Value::Null,
+            ));
         }
-        if self.mouse_sensitivity <= 0.0 {
-            self.mouse_sensitivity = 100.0;
+        self.resume_policies.retain(ResumePolicy::is_valid);
+
+        if !self.process_auto_refresh_secs.is_finite() || self.process_auto_refresh_secs < 0.0 {
+            issues.push(ConfigIssue::new(
+                "process_auto_refresh_secs",
+                IssueKind::NotPositive,
+                self.process_auto_refresh_secs,
+                0.0,
+            ));
+            self.process_auto_refresh_secs = 0.0;
+        } else if self.process_auto_refresh_secs > 0.0
+            && self.process_auto_refresh_secs < MIN_PROCESS_AUTO_REFRESH_SECS
+        {
+            issues.push(ConfigIssue::new(
+                "process_auto_refresh_secs",
+                IssueKind::BelowMinimum,
+                self.process_auto_refresh_secs,
+                MIN_PROCESS_AUTO_REFRESH_SECS,
+            ));
+            self.process_auto_refresh_secs = MIN_PROCESS_AUTO_REFRESH_SECS;
+        }
+
+        let language = sanitize_language(&self.language);
+        if language != self.language.to_lowercase() {
+            issues.push(ConfigIssue::new(
+                "language",
+                IssueKind::UnsupportedLanguage,
+                self.language.clone(),
+                language.clone(),
+            ));
+        }
+        self.language = language;
+
+        self.normalize_rules(&mut issues);
+        self.normalize_profiles(&mut issues);
+        self.normalize_ignored_processes();
+        issues
+    }
+
+    fn normalize_ignored_processes(&mut self) {
+        let mut seen = std::collections::HashSet::new();
+        let patterns = std::mem::take(&mut self.ignored_processes);
+        self.ignored_processes = patterns
+            .into_iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty() && seen.insert(pattern.to_lowercase()))
+            .collect();
+    }
+
+    fn normalize_rules(&mut self, issues: &mut Vec<ConfigIssue>) {
+        let mut seen_ids = std::collections::HashSet::new();
+        let rules = std::mem::take(&mut self.process_rules);
+        for (index, mut rule) in rules.into_iter().enumerate() {
+            let field = format!("process_rules[{index}]");
+            if let Some(sensitivity) = rule.mouse_sensitivity.filter(|value| !is_positive_finite(*value)) {
+                issues.push(ConfigIssue::new(
+                    format!("{field}.mouseSensitivity"),
+                    IssueKind::NotPositive,
+                    sensitivity,
+                    serde_json::Value::Null,
+                ));
+            }
+            for policy in rule.resume_policies.iter().flatten().filter(|policy| !policy.is_valid()) {
+                issues.push(ConfigIssue::new(
+                    format!("{field}.resumePolicies"),
+                    IssueKind::NotPositive,
+                    serde_json::to_value(policy).unwrap_or_default(),
+                    serde_json::Value::Null,
+                ));
+            }
+            rule.normalize();
+            if !rule.has_condition() {
+                issues.push(ConfigIssue::new(
+                    format!("{field}.processName"),
+                    IssueKind::EmptyProcessName,
+                    rule.id.clone(),
+                    serde_json::Value::Null,
+                ));
+                continue;
+            }
+            for (pattern_field, pattern) in invalid_rule_patterns(&rule) {
+                issues.push(ConfigIssue::new(
+                    format!("{field}.{pattern_field}"),
+                    IssueKind::InvalidPattern,
+                    pattern,
+                    serde_json::Value::Null,
+                ));
+            }
+            if rule.id.is_empty() || seen_ids.contains(&rule.id) {
+                let id = self.next_rule_id();
+                if !rule.id.is_empty() {
+                    issues.push(ConfigIssue::new(
+                        format!("{field}.id"),
+                        IssueKind::DuplicateRuleId,
+                        rule.id.clone(),
+                        id.clone(),
+                    ));
+                }
+                rule.id = id;
+            }
+            seen_ids.insert(rule.id.clone());
+            self.process_rules.push(rule);
         }
-        self.language = sanitize_language(&self.language);
-        self.selected_processes.sort();
-        self.selected_processes.dedup();
     }
 }
 
-#[derive(Debug, Clone)]
+/// Where the config directory came from, in order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigDirSource {
+    CliFlag,
+    Environment,
+    Portable,
+    LocalAppData,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLocation {
+    pub dir: PathBuf,
+    pub source: ConfigDirSource,
+}
+
+impl ConfigLocation {
+    /// Whether a relaunch (e.g. from the Run key) needs `--config-dir` to find the same directory.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn needs_explicit_flag(&self) -> bool {
+        matches!(self.source, ConfigDirSource::CliFlag | ConfigDirSource::Environment)
+    }
+}
+
+#[derive(Debug)]
 pub struct ConfigManager {
+    location: ConfigLocation,
     config_path: PathBuf,
     window_state_path: PathBuf,
+    recent_apps_path: PathBuf,
+    backup_dir: PathBuf,
+    config_backups: RollingBackups,
+    window_backups: RollingBackups,
+    /// Set while `config.json` comes from a newer build; saving would downgrade it.
+    read_only: AtomicBool,
 }
 
 impl ConfigManager {
-    pub fn load_or_create() -> Result<(Self, AppConfig)> {
-        let dir = determine_config_dir()?;
+    pub fn load_or_create() -> Result<(Self, AppConfig, Vec<ConfigIssue>)> {
+        Self::load_or_create_in(determine_config_dir()?)
+    }
+
+    /// Loads (or initialises) the config stored in an explicit directory, along with a report of
+    /// everything that had to be migrated, recovered or corrected on the way.
+    pub fn load_or_create_in(location: ConfigLocation) -> Result<(Self, AppConfig, Vec<ConfigIssue>)> {
+        let dir = location.dir.clone();
         fs::create_dir_all(&dir).context("config 디렉터리 생성 실패")?;
-        let config_path = dir.join(CONFIG_FILE_NAME);
-        let window_state_path = dir.join(WINDOW_STATE_FILE_NAME);
-
-        let mut config = if config_path.exists() {
-            let raw = fs::read_to_string(&config_path).context("config 파일을 읽을 수 없습니다")?;
-            match serde_json::from_str::<AppConfig>(&raw) {
-                Ok(mut cfg) => {
-                    cfg.normalize();
-                    cfg
+        let backup_dir = dir.join(BACKUP_DIR_NAME);
+        let manager = Self {
+            location,
+            config_path: dir.join(CONFIG_FILE_NAME),
+            window_state_path: dir.join(WINDOW_STATE_FILE_NAME),
+            recent_apps_path: dir.join(RECENT_APPS_FILE_NAME),
+            config_backups: RollingBackups::new(backup_dir.clone(), "config", CONFIG_BACKUP_LIMIT),
+            window_backups: RollingBackups::new(backup_dir.clone(), "window", WINDOW_STATE_BACKUP_LIMIT)
+                .with_min_interval(WINDOW_STATE_BACKUP_INTERVAL),
+            backup_dir,
+            read_only: AtomicBool::new(false),
+        };
+
+        let mut issues = Vec::new();
+        let mut config = if manager.config_path.exists() {
+            // Failing to read is not a reason to replace the file: it may only be locked.
+            let raw = read_to_string_retrying(&manager.config_path)
+                .with_context(|| format!("config 파일을 읽을 수 없습니다: {}", manager.config_path.display()))?;
+            match parse_config(&raw) {
+                Ok((config, outcome)) => {
+                    manager.note_outcome(outcome);
+                    if let Err(err) = manager.backup_before_upgrade(outcome) {
+                        // Without the original kept, writing the upgraded file back would lose it.
+                        tracing::warn!(?err, "스키마 변경 전 config를 백업하지 못해 읽기 전용으로 시작합니다");
+                        manager.read_only.store(true, Ordering::Relaxed);
+                    }
+                    issues.extend(migration_issue(outcome));
+                    config
                 }
                 Err(err) => {
-                    tracing::warn!(?err, path = %config_path.display(), "config 파싱 실패, 기본값 사용");
-                    AppConfig::default()
+                    tracing::warn!(?err, path = %manager.config_path.display(), "config 파싱 실패, 백업에서 복구를 시도합니다");
+                    issues.push(ConfigIssue::new(
+                        CONFIG_FILE_NAME,
+                        IssueKind::Unreadable,
+                        format!("{err:#}"),
+                        serde_json::Value::Null,
+                    ));
+                    let (config, recovered) = manager.recover_config();
+                    issues.extend(recovered);
+                    config
                 }
             }
         } else {
             AppConfig::default()
         };
 
-        config.normalize();
-        Ok((
-            Self {
-                config_path,
-                window_state_path,
-            },
-            config,
-        ))
+        issues.extend(config.normalize_with_report());
+        if !issues.is_empty() && !manager.is_read_only() {
+            if let Err(err) = manager.save(&config) {
+                tracing::warn!(?err, "마이그레이션/복구된 config 저장 실패");
+            }
+        }
+        Ok((manager, config, issues))
+    }
+
+    /// Validates the config in `location` without touching any file. Used by `--check-config`.
+    pub fn check(location: &ConfigLocation) -> Vec<ConfigIssue> {
+        match Self::read_only(location) {
+            Ok((_, issues)) => issues,
+            Err(err) => vec![ConfigIssue::new(
+                CONFIG_FILE_NAME,
+                IssueKind::Unreadable,
+                format!("{err:#}"),
+                serde_json::Value::Null,
+            )],
+        }
+    }
+
+    /// Reads the config in `location` into memory, migrated and normalised, without touching
+    /// any file. A missing file reads as the defaults.
+    pub fn read_only(location: &ConfigLocation) -> Result<(AppConfig, Vec<ConfigIssue>)> {
+        let path = location.dir.join(CONFIG_FILE_NAME);
+        if !path.exists() {
+            return Ok((AppConfig::default(), Vec::new()));
+        }
+
+        let raw = read_to_string_retrying(&path).context("config 파일을 읽을 수 없습니다")?;
+        let (mut config, outcome) = parse_config(&raw)?;
+        let mut issues: Vec<ConfigIssue> = migration_issue(outcome).into_iter().collect();
+        issues.extend(config.normalize_with_report());
+        Ok((config, issues))
+    }
+
+    pub fn location(&self) -> &ConfigLocation {
+        &self.location
+    }
+
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    /// A trace file under the `traces` directory of the config directory, timestamped unless
+    /// `file_name` is given. Only a bare file name is accepted, so a recording cannot be
+    /// pointed anywhere else.
+    pub fn new_trace_path(&self, file_name: Option<&str>) -> Result<PathBuf> {
+        let name = match file_name {
+            Some(name) => {
+                let bare = Path::new(name).file_name().is_some_and(|bare| bare == name);
+                if !bare || name.contains(['/', '\\']) {
+                    return Err(anyhow!("trace 파일 이름에는 경로를 넣을 수 없습니다: {name}"));
+                }
+                name.to_string()
+            }
+            None => format!("trace-{}.jsonl", chrono::Local::now().format("%Y%m%d-%H%M%S")),
+        };
+        Ok(self.location.dir.join(TRACE_DIR_NAME).join(name))
+    }
+
+    /// Re-reads `config.json` after an external edit. Unlike startup loading this never
+    /// quarantines, backs up or rewrites the file, since the writer may not be finished yet.
+    pub fn reload(&self) -> Result<(AppConfig, Vec<ConfigIssue>)> {
+        let raw = read_to_string_retrying(&self.config_path).context("config 파일을 읽을 수 없습니다")?;
+        let (mut config, outcome) = parse_config(&raw)?;
+        self.note_outcome(outcome);
+        let mut issues: Vec<ConfigIssue> = migration_issue(outcome).into_iter().collect();
+        issues.extend(config.normalize_with_report());
+        Ok((config, issues))
+    }
+
+    /// Whether saving is refused because the file on disk is from a newer build.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
+    }
+
+    fn note_outcome(&self, outcome: MigrationOutcome) {
+        self.read_only.store(outcome.is_from_newer_build(), Ordering::Relaxed);
+    }
+
+    /// Keeps the original `config.json` before a file in another schema is written back.
+    fn backup_before_upgrade(&self, outcome: MigrationOutcome) -> Result<()> {
+        if outcome.from == CONFIG_SCHEMA_VERSION {
+            return Ok(());
+        }
+        let backup = backup_before_migration(&self.config_path, &self.backup_dir, outcome.from)?;
+        tracing::info!(
+            from = outcome.from,
+            to = CONFIG_SCHEMA_VERSION,
+            backup = %backup.display(),
+            "config 스키마 버전이 달라 원본을 백업했습니다"
+        );
+        Ok(())
+    }
+
+    /// Quarantines the unreadable `config.json` and restores the newest backup that still parses.
+    /// Falls back to defaults only when no backup is usable. A backup from a newer build is
+    /// restored read-only, like such a `config.json` would be.
+    fn recover_config(&self) -> (AppConfig, Vec<ConfigIssue>) {
+        match quarantine(&self.config_path) {
+            Ok(moved) => tracing::warn!(path = %moved.display(), "손상된 config를 격리했습니다"),
+            Err(err) => tracing::warn!(?err, "손상된 config 격리 실패"),
+        }
+
+        let backups = self.config_backups.list().unwrap_or_else(|err| {
+            tracing::warn!(?err, "config 백업 목록을 읽을 수 없습니다");
+            Vec::new()
+        });
+        for backup in backups {
+            let parsed = read_to_string_retrying(&backup)
+                .context("백업 config를 읽을 수 없습니다")
+                .and_then(|raw| {
+                    if !is_valid_config_backup(&raw) {
+                        return Err(anyhow!("schema_version이 없는 백업입니다"));
+                    }
+                    parse_config(&raw)
+                });
+            match parsed {
+                Ok((config, outcome)) => {
+                    tracing::info!(path = %backup.display(), "백업에서 config를 복구했습니다");
+                    self.note_outcome(outcome);
+                    let issue = ConfigIssue::new(
+                        CONFIG_FILE_NAME,
+                        IssueKind::RestoredFromBackup,
+                        serde_json::Value::Null,
+                        backup.display().to_string(),
+                    );
+                    let mut issues = vec![issue];
+                    issues.extend(migration_issue(outcome));
+                    return (config, issues);
+                }
+                Err(err) => tracing::warn!(?err, path = %backup.display(), "백업 config도 읽을 수 없습니다"),
+            }
+        }
+
+        tracing::warn!("사용 가능한 config 백업이 없어 기본값을 사용합니다");
+        let issue = ConfigIssue::new(
+            CONFIG_FILE_NAME,
+            IssueKind::ResetToDefaults,
+            serde_json::Value::Null,
+            serde_json::Value::Null,
+        );
+        (AppConfig::default(), vec![issue])
     }
 
     pub fn save(&self, config: &AppConfig) -> Result<()> {
+        if self.is_read_only() {
+            return Err(anyhow!(
+                "더 최신 버전의 Langcon이 저장한 config라 덮어쓰지 않습니다: {}",
+                self.config_path.display()
+            ));
+        }
         let mut cfg = config.clone();
+        cfg.schema_version = CONFIG_SCHEMA_VERSION;
         cfg.normalize();
         let serialized = serde_json::to_string_pretty(&cfg).context("config 직렬화 실패")?;
-        fs::write(&self.config_path, serialized).context("config 저장 실패")?;
+        if let Err(err) = self
+            .config_backups
+            .snapshot(&self.config_path, is_valid_config_backup)
+        {
+            tracing::warn!(?err, "config 백업 실패");
+        }
+        write_atomic(&self.config_path, serialized.as_bytes()).context("config 저장 실패")?;
         Ok(())
     }
 
@@ -128,31 +560,187 @@ impl ConfigManager {
         match serde_json::from_str::<WindowState>(&raw) {
             Ok(state) => Ok(Some(state)),
             Err(err) => {
-                tracing::warn!(?err, path = %self.window_state_path.display(), "window state 파싱 실패, 무시합니다");
-                Ok(None)
+                tracing::warn!(?err, path = %self.window_state_path.display(), "window state 파싱 실패, 백업에서 복구를 시도합니다");
+                Ok(self.recover_window_state())
             }
         }
     }
 
+    fn recover_window_state(&self) -> Option<WindowState> {
+        if let Err(err) = quarantine(&self.window_state_path) {
+            tracing::warn!(?err, "손상된 window state 격리 실패");
+        }
+
+        let restored = self
+            .window_backups
+            .list()
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|backup| {
+                let raw = fs::read_to_string(&backup).ok()?;
+                serde_json::from_str::<WindowState>(&raw).ok()
+            })?;
+        if let Err(err) = self.save_window_state(&restored) {
+            tracing::warn!(?err, "복구된 window state 저장 실패");
+        }
+        Some(restored)
+    }
+
     pub fn save_window_state(&self, state: &WindowState) -> Result<()> {
         let serialized = serde_json::to_string_pretty(state).context("window state 직렬화 실패")?;
-        fs::write(&self.window_state_path, serialized).context("window state 저장 실패")?;
+        if let Err(err) = self
+            .window_backups
+            .snapshot(&self.window_state_path, |raw| serde_json::from_str::<WindowState>(raw).is_ok())
+        {
+            tracing::warn!(?err, "window state 백업 실패");
+        }
+        write_atomic(&self.window_state_path, serialized.as_bytes()).context("window state 저장 실패")?;
+        Ok(())
+    }
+
+    /// Focus history is a convenience, so an unreadable file is set aside and history starts over.
+    pub fn load_recent_apps(&self) -> RecentApps {
+        if !self.recent_apps_path.exists() {
+            return RecentApps::default();
+        }
+
+        let parsed = fs::read_to_string(&self.recent_apps_path)
+            .context("최근 사용 앱 파일을 읽을 수 없습니다")
+            .and_then(|raw| serde_json::from_str(&raw).context("최근 사용 앱 파싱 실패"));
+        match parsed {
+            Ok(recent) => recent,
+            Err(err) => {
+                tracing::warn!(?err, path = %self.recent_apps_path.display(), "최근 사용 앱 기록을 새로 시작합니다");
+                if let Err(err) = quarantine(&self.recent_apps_path) {
+                    tracing::warn!(?err, "손상된 최근 사용 앱 파일 격리 실패");
+                }
+                RecentApps::default()
+            }
+        }
+    }
+
+    pub fn save_recent_apps(&self, recent: &RecentApps) -> Result<()> {
+        let serialized = serde_json::to_string_pretty(recent).context("최근 사용 앱 직렬화 실패")?;
+        write_atomic(&self.recent_apps_path, serialized.as_bytes()).context("최근 사용 앱 저장 실패")?;
         Ok(())
     }
 }
 
-fn determine_config_dir() -> Result<PathBuf> {
-    if let Some(path) = std::env::var_os("LOCALAPPDATA") {
-        return Ok(PathBuf::from(path).join("N0Works").join("Langcon"));
+/// Parses and migrates a raw config in memory.
+fn parse_config(raw: &str) -> Result<(AppConfig, MigrationOutcome)> {
+    let mut value: serde_json::Value = serde_json::from_str(raw).context("config JSON 파싱 실패")?;
+    let outcome = migrate(&mut value)?;
+    let mut config: AppConfig = serde_json::from_value(value).context("config 역직렬화 실패")?;
+    config.schema_version = CONFIG_SCHEMA_VERSION;
+    Ok((config, outcome))
+}
+
+/// A backup must be a config this app wrote: `AppConfig` alone accepts almost any object
+/// thanks to `#[serde(default)]`, so a numeric `schema_version` is required as well.
+fn is_valid_config_backup(raw: &str) -> bool {
+    let has_version = serde_json::from_str::<serde_json::Value>(raw)
+        .ok()
+        .is_some_and(|value| value.get("schema_version").is_some_and(serde_json::Value::is_u64));
+    has_version && parse_config(raw).is_ok()
+}
+
+fn migration_issue(outcome: MigrationOutcome) -> Option<ConfigIssue> {
+    let kind = if outcome.is_from_newer_build() {
+        IssueKind::NewerSchema
+    } else if outcome.changed() {
+        IssueKind::Migrated
+    } else {
+        return None;
+    };
+    Some(ConfigIssue::new("schema_version", kind, outcome.from, outcome.to))
+}
+
+/// Resolves the config directory: `--config-dir <path>`, then `LANGCON_CONFIG_DIR`,
+/// then portable mode (a `langcon.portable` file next to the executable), then `LOCALAPPDATA`.
+pub fn determine_config_dir() -> Result<ConfigLocation> {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    resolve_config_dir(
+        std::env::args(),
+        std::env::var_os(CONFIG_DIR_ENV),
+        exe_dir,
+        std::env::var_os("LOCALAPPDATA"),
+    )
+}
+
+/// [`determine_config_dir`] with its inputs passed in.
+fn resolve_config_dir(
+    args: impl IntoIterator<Item = String>,
+    env_dir: Option<OsString>,
+    exe_dir: Option<PathBuf>,
+    local_app_data: Option<OsString>,
+) -> Result<ConfigLocation> {
+    if let Some(path) = config_dir_from_args(args) {
+        return Ok(ConfigLocation {
+            dir: absolutize(path)?,
+            source: ConfigDirSource::CliFlag,
+        });
+    }
+
+    if let Some(path) = env_dir.filter(|value| !value.is_empty()) {
+        return Ok(ConfigLocation {
+            dir: absolutize(PathBuf::from(path))?,
+            source: ConfigDirSource::Environment,
+        });
+    }
+
+    if let Some(exe_dir) = exe_dir {
+        if exe_dir.join(PORTABLE_MARKER_FILE_NAME).is_file() {
+            return Ok(ConfigLocation {
+                dir: exe_dir.join(PORTABLE_DATA_DIR_NAME),
+                source: ConfigDirSource::Portable,
+            });
+        }
+    }
+
+    if let Some(path) = local_app_data {
+        return Ok(ConfigLocation {
+            dir: PathBuf::from(path).join("N0Works").join("Langcon"),
+            source: ConfigDirSource::LocalAppData,
+        });
     }
 
     if let Some(path) = directories::BaseDirs::new().map(|dirs| dirs.data_local_dir().to_path_buf()) {
-        return Ok(path.join("N0Works").join("Langcon"));
+        return Ok(ConfigLocation {
+            dir: path.join("N0Works").join("Langcon"),
+            source: ConfigDirSource::LocalAppData,
+        });
     }
 
     Err(anyhow!("LOCALAPPDATA 환경 변수를 찾을 수 없습니다."))
 }
 
+/// Accepts both `--config-dir <path>` and `--config-dir=<path>`.
+fn config_dir_from_args(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == CONFIG_DIR_FLAG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg
+            .strip_prefix(CONFIG_DIR_FLAG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+fn absolutize(path: PathBuf) -> Result<PathBuf> {
+    if path.is_absolute() {
+        return Ok(path);
+    }
+    let cwd = std::env::current_dir().context("현재 작업 디렉터리를 확인할 수 없습니다")?;
+    Ok(cwd.join(path))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowState {
@@ -165,25 +753,268 @@ pub struct WindowState {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppConfigDto {
+    pub active_profile: String,
+    pub profiles: Vec<String>,
     pub selected_processes: Vec<String>,
+    pub process_rules: Vec<ProcessRule>,
     pub use_auto_to_en: bool,
     pub use_mouse_move_event: bool,
     pub detect_interval_secs: f32,
+    pub max_detect_interval_secs: f32,
     pub mouse_sensitivity: f32,
+    pub resume_policies: Vec<ResumePolicy>,
     pub start_with_windows: bool,
     pub language: String,
+    pub ignored_processes: Vec<String>,
+    pub process_auto_refresh_secs: f32,
 }
 
 impl From<&AppConfig> for AppConfigDto {
     fn from(value: &AppConfig) -> Self {
+        let mut selected_processes: Vec<String> = value
+            .process_rules
+            .iter()
+            // Only the picker's own rules; scoped and pattern rules are edited in the rule list.
+            .filter(|rule| rule.is_picked())
+            .map(|rule| rule.process_name.clone())
+            .collect();
+        selected_processes.sort();
+        selected_processes.dedup();
         Self {
-            selected_processes: value.selected_processes.clone(),
+            active_profile: value.active_profile.clone(),
+            profiles: value.profile_names(),
+            selected_processes,
+            process_rules: value.process_rules.clone(),
             use_auto_to_en: value.use_auto_to_en,
             use_mouse_move_event: value.use_mouse_move_event,
             detect_interval_secs: value.detect_interval_secs,
+            max_detect_interval_secs: value.max_detect_interval_secs,
             mouse_sensitivity: value.mouse_sensitivity,
+            resume_policies: value.resume_policies.clone(),
             start_with_windows: value.start_with_windows,
             language: sanitize_language(&value.language),
+            ignored_processes: value.ignored_processes.clone(),
+            process_auto_refresh_secs: value.process_auto_refresh_secs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(dir: &Path) -> ConfigLocation {
+        ConfigLocation {
+            dir: dir.to_path_buf(),
+            source: ConfigDirSource::CliFlag,
         }
     }
+
+    #[test]
+    fn trace_files_stay_in_the_traces_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let (manager, _, _) = ConfigManager::load_or_create_in(location(dir.path())).unwrap();
+        let traces = dir.path().join(TRACE_DIR_NAME);
+
+        assert_eq!(manager.new_trace_path(Some("bug.jsonl")).unwrap(), traces.join("bug.jsonl"));
+        assert!(manager.new_trace_path(None).unwrap().starts_with(&traces));
+        for name in ["../config.json", "..", "", "sub/bug.jsonl", r"C:\Windows\win.ini"] {
+            assert!(manager.new_trace_path(Some(name)).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn restores_unparsable_config_from_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let (manager, mut config, _) = ConfigManager::load_or_create_in(location(dir.path())).unwrap();
+        config.use_auto_to_en = false;
+        manager.save(&config).unwrap();
+        manager.save(&config).unwrap();
+        fs::write(dir.path().join(CONFIG_FILE_NAME), "{ not json").unwrap();
+
+        let (_, restored, issues) = ConfigManager::load_or_create_in(location(dir.path())).unwrap();
+
+        assert!(!restored.use_auto_to_en);
+        assert!(issues.iter().any(|issue| issue.kind == IssueKind::RestoredFromBackup));
+        let quarantined = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.file_name().to_string_lossy().starts_with("config.corrupt-"));
+        assert!(quarantined);
+    }
+
+    #[test]
+    fn newer_backup_is_restored_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = dir.path().join(BACKUP_DIR_NAME);
+        fs::create_dir(&backups).unwrap();
+        let newer = format!(r#"{{"schema_version": {}, "use_auto_to_en": false}}"#, CONFIG_SCHEMA_VERSION + 1);
+        fs::write(backups.join("config.bak.20990101-000000-000.json"), &newer).unwrap();
+        fs::write(dir.path().join(CONFIG_FILE_NAME), "{ not json").unwrap();
+
+        let (manager, restored, issues) = ConfigManager::load_or_create_in(location(dir.path())).unwrap();
+
+        assert!(!restored.use_auto_to_en);
+        assert!(manager.is_read_only());
+        assert!(issues.iter().any(|issue| issue.kind == IssueKind::NewerSchema));
+        assert!(!dir.path().join(CONFIG_FILE_NAME).exists());
+        assert!(manager.save(&restored).is_err());
+    }
+
+    #[test]
+    fn failed_upgrade_backup_starts_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        // A file where the backup directory should be makes the backup fail.
+        fs::write(dir.path().join(BACKUP_DIR_NAME), "").unwrap();
+        let legacy = r#"{"selected_process_list": ["javaw.exe"]}"#;
+        fs::write(dir.path().join(CONFIG_FILE_NAME), legacy).unwrap();
+
+        let (manager, config, _) = ConfigManager::load_or_create_in(location(dir.path())).unwrap();
+
+        assert_eq!(config.process_rules[0].process_name, "javaw.exe");
+        assert!(manager.is_read_only());
+        assert_eq!(fs::read_to_string(dir.path().join(CONFIG_FILE_NAME)).unwrap(), legacy);
+    }
+
+    #[test]
+    fn read_failure_leaves_config_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory cannot be read as a file, standing in for a file that stays locked.
+        fs::create_dir(dir.path().join(CONFIG_FILE_NAME)).unwrap();
+
+        assert!(ConfigManager::load_or_create_in(location(dir.path())).is_err());
+        assert!(dir.path().join(CONFIG_FILE_NAME).is_dir());
+    }
+
+    #[test]
+    fn reload_does_not_back_up_or_rewrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let (manager, _, _) = ConfigManager::load_or_create_in(location(dir.path())).unwrap();
+        let legacy = r#"{"selected_process_list": ["javaw.exe"]}"#;
+        fs::write(dir.path().join(CONFIG_FILE_NAME), legacy).unwrap();
+
+        let (config, issues) = manager.reload().unwrap();
+
+        assert_eq!(config.process_rules[0].process_name, "javaw.exe");
+        assert!(issues.iter().any(|issue| issue.kind == IssueKind::Migrated));
+        assert!(!dir.path().join(BACKUP_DIR_NAME).exists());
+        assert_eq!(fs::read_to_string(dir.path().join(CONFIG_FILE_NAME)).unwrap(), legacy);
+    }
+
+    fn args(items: &[&str]) -> Vec<String> {
+        std::iter::once("Langcon.exe")
+            .chain(items.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    fn portable_exe_dir() -> tempfile::TempDir {
+        let exe_dir = tempfile::tempdir().unwrap();
+        fs::write(exe_dir.path().join(PORTABLE_MARKER_FILE_NAME), "").unwrap();
+        exe_dir
+    }
+
+    #[test]
+    fn config_dir_flag_wins_over_everything() {
+        let exe_dir = portable_exe_dir();
+        for flag in [&["--config-dir", "/cfg/flag"][..], &["--config-dir=/cfg/flag"][..]] {
+            let location = resolve_config_dir(
+                args(flag),
+                Some("/cfg/env".into()),
+                Some(exe_dir.path().to_path_buf()),
+                Some("/appdata".into()),
+            )
+            .unwrap();
+            assert_eq!(location.source, ConfigDirSource::CliFlag);
+            assert_eq!(location.dir, PathBuf::from("/cfg/flag"));
+        }
+    }
+
+    #[test]
+    fn environment_wins_over_portable_and_local_app_data() {
+        let exe_dir = portable_exe_dir();
+        let location = resolve_config_dir(
+            args(&[]),
+            Some("/cfg/env".into()),
+            Some(exe_dir.path().to_path_buf()),
+            Some("/appdata".into()),
+        )
+        .unwrap();
+        assert_eq!(location.source, ConfigDirSource::Environment);
+        assert_eq!(location.dir, PathBuf::from("/cfg/env"));
+    }
+
+    #[test]
+    fn portable_marker_wins_over_local_app_data() {
+        let exe_dir = portable_exe_dir();
+        let location = resolve_config_dir(
+            args(&[]),
+            Some("".into()),
+            Some(exe_dir.path().to_path_buf()),
+            Some("/appdata".into()),
+        )
+        .unwrap();
+        assert_eq!(location.source, ConfigDirSource::Portable);
+        assert_eq!(location.dir, exe_dir.path().join(PORTABLE_DATA_DIR_NAME));
+    }
+
+    #[test]
+    fn falls_back_to_local_app_data() {
+        let exe_dir = tempfile::tempdir().unwrap();
+        let location = resolve_config_dir(
+            args(&[]),
+            None,
+            Some(exe_dir.path().to_path_buf()),
+            Some("/appdata".into()),
+        )
+        .unwrap();
+        assert_eq!(location.source, ConfigDirSource::LocalAppData);
+        assert_eq!(location.dir, PathBuf::from("/appdata").join("N0Works").join("Langcon"));
+    }
+
+    #[test]
+    fn relative_config_dir_is_made_absolute() {
+        let location = resolve_config_dir(args(&["--config-dir", "cfg"]), None, None, None).unwrap();
+        assert_eq!(location.dir, std::env::current_dir().unwrap().join("cfg"));
+    }
+
+    #[test]
+    fn creates_defaults_in_an_empty_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("nested");
+        let (manager, config, issues) = ConfigManager::load_or_create_in(location(&target)).unwrap();
+
+        assert_eq!(config, AppConfig::default());
+        assert!(issues.is_empty());
+        assert_eq!(manager.config_path(), target.join(CONFIG_FILE_NAME));
+
+        let mut edited = config.clone();
+        edited.detect_interval_secs = 1.0;
+        edited.max_detect_interval_secs = 1.0;
+        manager.save(&edited).unwrap();
+        let (_, reloaded, _) = ConfigManager::load_or_create_in(location(&target)).unwrap();
+        assert_eq!(reloaded, edited);
+    }
+
+    #[test]
+    fn config_from_a_newer_build_is_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let raw = format!(r#"{{"schema_version": {}, "future_field": 1}}"#, CONFIG_SCHEMA_VERSION + 1);
+        fs::write(dir.path().join(CONFIG_FILE_NAME), &raw).unwrap();
+
+        let (manager, config, issues) = ConfigManager::load_or_create_in(location(dir.path())).unwrap();
+
+        assert!(manager.is_read_only());
+        assert!(issues.iter().any(|issue| issue.kind == IssueKind::NewerSchema));
+        assert!(manager.save(&config).is_err());
+        assert_eq!(fs::read_to_string(dir.path().join(CONFIG_FILE_NAME)).unwrap(), raw);
+    }
+
+    #[test]
+    fn backups_need_a_schema_version() {
+        assert!(!is_valid_config_backup("{}"));
+        assert!(!is_valid_config_backup(r#"{"schema_version": "3"}"#));
+        let saved = serde_json::to_string(&AppConfig::default()).unwrap();
+        assert!(is_valid_config_backup(&saved));
+    }
 }
@@ -0,0 +1,575 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ime::ImeStatus;
+use crate::process::ProcessInfo;
+use crate::rules::EffectiveSettings;
+use crate::state::StatusMessage;
+
+/// The same status message is not shown again within this window.
+const STATUS_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// Everything the engine gets to know about one monitor tick.
+#[derive(Debug, Clone)]
+pub struct Observation<'a> {
+    /// The foreground process, `None` when nothing has focus.
+    pub process: Option<&'a ProcessInfo>,
+    /// Settings of the rule that applies to `process`, `None` when no include rule does.
+    pub settings: Option<EffectiveSettings>,
+    /// Id of the rule `settings` come from.
+    pub rule_id: Option<&'a str>,
+    pub ime: ImeStatus,
+    pub cursor: Option<(i32, i32)>,
+    /// Time since the last key press, `None` when the platform cannot tell.
+    pub keyboard_idle: Option<Duration>,
+    pub use_auto_to_en: bool,
+    pub at: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Action {
+    /// Switch the focused window to `target`, then report the outcome to [`Engine::switched`].
+    Switch(ImeStatus),
+    EmitStatus(StatusMessage),
+    /// Auto-switching was paused (`enabled`) or resumed for this process under this rule.
+    UpdateOverride {
+        #[serde(flatten)]
+        key: OverrideKey,
+        enabled: bool,
+    },
+}
+
+/// What a manual override pauses: one process under one rule. A browser paused while a
+/// tab-specific rule applies keeps switching under its other rules.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideKey {
+    /// Empty in traces recorded before overrides were keyed by rule.
+    #[serde(default)]
+    pub rule_id: String,
+    pub process_name: String,
+}
+
+impl OverrideKey {
+    pub fn new(rule_id: impl Into<String>, process_name: impl Into<String>) -> Self {
+        Self {
+            rule_id: rule_id.into(),
+            process_name: process_name.into(),
+        }
+    }
+}
+
+/// A process the user switched by hand, see [`Engine::manual_override_for`].
+#[derive(Debug, Clone, Copy)]
+struct ManualOverride {
+    started_at: Instant,
+    /// Set for overrides requested for a fixed time. Until then the app stays paused whatever
+    /// its input mode and resume policies; afterwards automatic switching resumes.
+    expires_at: Option<Instant>,
+    /// Another process had focus since the override started.
+    left_focus: bool,
+}
+
+impl ManualOverride {
+    fn expired(&self, at: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| at >= expires_at)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideSnapshot {
+    #[serde(flatten)]
+    pub key: OverrideKey,
+    /// Milliseconds between the start of the override and the snapshot.
+    pub age_ms: u64,
+    /// Milliseconds left of a time-limited override.
+    #[serde(default)]
+    pub remaining_ms: Option<u64>,
+    pub left_focus: bool,
+}
+
+/// The state a trace starts from, so a recording begun mid-session replays the same way.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineSnapshot {
+    pub manual_overrides: Vec<OverrideSnapshot>,
+    pub previous: Option<(String, ImeStatus)>,
+    pub last_cursor: Option<(i32, i32)>,
+    /// The last status shown and how many milliseconds before the snapshot, for the cooldown.
+    pub last_status: Option<(StatusMessage, u64)>,
+}
+
+/// Decides when to switch the input mode of the focused app.
+///
+/// Platform independent: the monitor feeds it [`Observation`]s and carries out the returned
+/// [`Action`]s, so the behaviour can be driven with scripted inputs.
+#[derive(Debug, Default)]
+pub struct Engine {
+    /// Processes whose input mode the user changed by hand; left alone until resumed.
+    manual_overrides: HashMap<OverrideKey, ManualOverride>,
+    /// Focused process name and input mode as last seen.
+    previous: Option<(String, ImeStatus)>,
+    /// Where the cursor was when it last counted as moved, or when tracking or the latest
+    /// override started. Only tracked while a rule with `use_mouse_move_event` applies.
+    last_cursor: Option<(i32, i32)>,
+    /// Shown instead of the switch announcement if the pending switch does not toggle.
+    pending_status: Option<StatusMessage>,
+    last_status: Option<(StatusMessage, Instant)>,
+}
+
+impl Engine {
+    /// Restores a [`snapshot`](Self::snapshot) taken at `at`.
+    pub fn from_snapshot(snapshot: EngineSnapshot, at: Instant) -> Self {
+        let last_status = snapshot.last_status.and_then(|(message, age_ms)| {
+            Some((message, at.checked_sub(Duration::from_millis(age_ms))?))
+        });
+        let manual_overrides = snapshot
+            .manual_overrides
+            .into_iter()
+            .map(|entry| {
+                let started_at = at
+                    .checked_sub(Duration::from_millis(entry.age_ms))
+                    .unwrap_or(at);
+                let expires_at = entry
+                    .remaining_ms
+                    .and_then(|remaining_ms| at.checked_add(Duration::from_millis(remaining_ms)));
+                let state = ManualOverride {
+                    started_at,
+                    expires_at,
+                    left_focus: entry.left_focus,
+                };
+                (entry.key, state)
+            })
+            .collect();
+        Self {
+            manual_overrides,
+            previous: snapshot.previous,
+            last_cursor: snapshot.last_cursor,
+            pending_status: None,
+            last_status,
+        }
+    }
+
+    pub fn snapshot(&self, at: Instant) -> EngineSnapshot {
+        let mut manual_overrides: Vec<OverrideSnapshot> = self
+            .manual_overrides
+            .iter()
+            .map(|(key, state)| OverrideSnapshot {
+                key: key.clone(),
+                age_ms: at.saturating_duration_since(state.started_at).as_millis() as u64,
+                remaining_ms: state.expires_at.map(|expires_at| {
+                    expires_at.saturating_duration_since(at).as_millis() as u64
+                }),
+                left_focus: state.left_focus,
+            })
+            .collect();
+        manual_overrides.sort_by(|a, b| a.key.cmp(&b.key));
+        let last_status = self.last_status.as_ref().map(|(message, shown_at)| {
+            let age = at.saturating_duration_since(*shown_at);
+            (message.clone(), age.as_millis() as u64)
+        });
+        EngineSnapshot {
+            manual_overrides,
+            previous: self.previous.clone(),
+            last_cursor: self.last_cursor,
+            last_status,
+        }
+    }
+
+    pub fn observe(&mut self, observation: &Observation) -> Vec<Action> {
+        // The override of the focused app, if a rule applies to it, ends below with a switch;
+        // every other expired one just ends.
+        let focused = observation
+            .process
+            .filter(|_| observation.settings.is_some())
+            .map(|process| OverrideKey::new(observation.rule_id.unwrap_or_default(), &process.name));
+        for (key, state) in &mut self.manual_overrides {
+            if focused.as_ref() != Some(key) {
+                state.left_focus = true;
+            }
+        }
+        let mut actions: Vec<Action> = self
+            .expired_overrides(observation.at)
+            .filter(|key| Some(*key) != focused.as_ref())
+            .map(|key| Action::UpdateOverride {
+                key: key.clone(),
+                enabled: false,
+            })
+            .collect();
+        let Some(process) = observation.process else {
+            self.previous = None;
+            return actions;
+        };
+        let name = &process.name;
+        let ime = observation.ime;
+        let previous = self.previous.replace((name.clone(), ime));
+        let (Some(settings), Some(key)) = (observation.settings, focused) else {
+            return actions;
+        };
+        let target = settings.target_mode.ime_status();
+
+        let current = self.manual_overrides.get(&key).copied();
+        let expired = current.is_some_and(|state| state.expired(observation.at));
+        let held = current.is_some_and(|state| state.expires_at.is_some()) && !expired;
+        let was_manual = current.is_some() && !expired;
+        // The mode moved away from the target while this app kept focus: the user pressed the
+        // IME key, so stop switching it back.
+        let changed_by_hand = previous.is_some_and(|(previous_name, previous_ime)| {
+            previous_name == *name
+                && previous_ime == target
+                && ime != target
+                && ime != ImeStatus::Unknown
+        });
+        let mut manual = held || ((was_manual || changed_by_hand) && ime != target);
+        let mut should_switch = observation.use_auto_to_en && ime != target && !manual;
+        let mut status = None;
+
+        if expired && !manual && ime != target {
+            should_switch = true;
+            status = Some(StatusMessage::with_values(
+                "toast.status.overrideExpired",
+                [("name", name.clone())],
+            ));
+        }
+
+        if !settings.use_mouse_move_event {
+            self.last_cursor = None;
+        } else if let Some(position) = observation.cursor {
+            // Measured from where the cursor rested rather than from the previous poll, so
+            // the distance to cover does not shrink while polling slows down.
+            let anchor = *self.last_cursor.get_or_insert(position);
+            let moved = distance(anchor, position) >= settings.mouse_sensitivity;
+            if moved {
+                self.last_cursor = Some(position);
+                if !held {
+                    manual = false;
+                    if ime != target {
+                        should_switch = true;
+                        status = Some(StatusMessage::with_values(
+                            "toast.status.mouseMove",
+                            [("name", name.clone())],
+                        ));
+                    }
+                }
+            }
+        }
+
+        if manual && was_manual && !held {
+            if let Some(reason) = self.resume_reason(&key, &settings, observation) {
+                manual = false;
+                should_switch = true;
+                status = Some(StatusMessage::with_values(reason, [("name", name.clone())]));
+            }
+        }
+
+        // An override that expired while the user paused again by hand restarts untimed.
+        if manual != current.is_some() || (manual && expired) {
+            actions.push(Action::UpdateOverride {
+                key,
+                enabled: manual,
+            });
+        }
+        if should_switch {
+            self.pending_status = status;
+            actions.push(Action::Switch(target));
+        } else if let Some(message) = status {
+            actions.extend(self.announce(message, observation.at).map(Action::EmitStatus));
+        }
+        actions
+    }
+
+    /// Outcome of an [`Action::Switch`]; `toggled` is false when nothing had to change or the
+    /// switch failed. Returns the status to show, if any.
+    pub fn switched(&mut self, toggled: bool, at: Instant) -> Option<StatusMessage> {
+        let pending = self.pending_status.take();
+        let message = match (&self.previous, toggled) {
+            (Some((name, _)), true) => Some(StatusMessage::with_values(
+                "toast.status.autoSwitch",
+                [("name", name.clone())],
+            )),
+            _ => pending,
+        };
+        message.and_then(|message| self.announce(message, at))
+    }
+
+    /// Records what is on screen now when something other than the engine changed it (the
+    /// toggle command), so the next observation is compared against it. Returns whether that
+    /// differs from what the engine last saw.
+    pub fn note_focus(&mut self, focus: Option<(String, ImeStatus)>) -> bool {
+        let changed = self.previous != focus;
+        self.previous = focus;
+        changed
+    }
+
+    /// Enabling (re)starts the override at `at`, which is also when
+    /// [`ResumePolicy::Timeout`](crate::rules::ResumePolicy::Timeout) starts counting. With a
+    /// `duration` the override holds until then regardless of resume policies.
+    pub fn set_manual_override(
+        &mut self,
+        key: &OverrideKey,
+        enabled: bool,
+        at: Instant,
+        duration: Option<Duration>,
+    ) {
+        if enabled {
+            let state = ManualOverride {
+                started_at: at,
+                expires_at: duration.and_then(|duration| at.checked_add(duration)),
+                left_focus: false,
+            };
+            self.manual_overrides.insert(key.clone(), state);
+            // Only movement after pausing resumes it.
+            self.last_cursor = None;
+        } else {
+            self.manual_overrides.remove(key);
+        }
+    }
+
+    pub fn manual_override_for(&self, key: &OverrideKey) -> bool {
+        self.manual_overrides.contains_key(key)
+    }
+
+    /// When the override of `key` expires, if it is time-limited.
+    pub fn manual_override_expiry(&self, key: &OverrideKey) -> Option<Instant> {
+        self.manual_overrides.get(key)?.expires_at
+    }
+
+    pub fn manual_overrides(&self) -> impl Iterator<Item = &OverrideKey> {
+        self.manual_overrides.keys()
+    }
+
+    /// Keys in sorted order, so recorded actions replay identically.
+    fn expired_overrides(&self, at: Instant) -> impl Iterator<Item = &OverrideKey> {
+        let mut expired: Vec<&OverrideKey> = self
+            .manual_overrides
+            .iter()
+            .filter(|(_, state)| state.expired(at))
+            .map(|(key, _)| key)
+            .collect();
+        expired.sort_unstable();
+        expired.into_iter()
+    }
+
+    /// Status key of the first resume policy that ends the override of `key`, if any.
+    fn resume_reason(
+        &self,
+        key: &OverrideKey,
+        settings: &EffectiveSettings,
+        observation: &Observation,
+    ) -> Option<&'static str> {
+        let state = self.manual_overrides.get(key)?;
+        if settings.resume_on_focus_return && state.left_focus {
+            return Some("toast.status.focusReturn");
+        }
+        let paused_for = observation.at.saturating_duration_since(state.started_at);
+        if settings
+            .resume_after_secs
+            .is_some_and(|secs| paused_for.as_secs_f32() >= secs)
+        {
+            return Some("toast.status.resumeTimeout");
+        }
+        let keyboard_idle = settings
+            .resume_after_keyboard_idle_secs
+            .zip(observation.keyboard_idle);
+        if keyboard_idle.is_some_and(|(secs, idle)| idle.as_secs_f32() >= secs) {
+            return Some("toast.status.keyboardIdle");
+        }
+        None
+    }
+
+    fn announce(&mut self, message: StatusMessage, at: Instant) -> Option<StatusMessage> {
+        if let Some((last, last_at)) = &self.last_status {
+            let repeated = last.key == message.key && last.values == message.values;
+            if repeated && at.saturating_duration_since(*last_at) < STATUS_COOLDOWN {
+                return None;
+            }
+        }
+        self.last_status = Some((message.clone(), at));
+        Some(message)
+    }
+}
+
+fn distance(a: (i32, i32), b: (i32, i32)) -> f32 {
+    let dx = (a.0 - b.0) as f32;
+    let dy = (a.1 - b.1) as f32;
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::TargetInputMode;
+
+    fn process(name: &str) -> ProcessInfo {
+        ProcessInfo {
+            pid: 1,
+            name: name.into(),
+            ..ProcessInfo::default()
+        }
+    }
+
+    fn settings() -> EffectiveSettings {
+        EffectiveSettings::new(TargetInputMode::English, true, 10.0, &[])
+    }
+
+    /// An observation of `process` under `rule_id`, `secs` seconds after `start`.
+    fn observe<'a>(
+        process: &'a ProcessInfo,
+        rule_id: &'a str,
+        ime: ImeStatus,
+        start: Instant,
+        secs: f32,
+    ) -> Observation<'a> {
+        Observation {
+            process: Some(process),
+            settings: Some(settings()),
+            rule_id: Some(rule_id),
+            ime,
+            cursor: Some((0, 0)),
+            keyboard_idle: None,
+            use_auto_to_en: true,
+            at: start + Duration::from_secs_f32(secs),
+        }
+    }
+
+    #[test]
+    fn overrides_are_kept_per_rule() {
+        let browser = process("browser.exe");
+        let start = Instant::now();
+        let mut engine = Engine::default();
+        let docs = OverrideKey::new("rule-docs", "browser.exe");
+        engine.set_manual_override(&docs, true, start, None);
+
+        // Another tab of the same browser, matched by a different rule, still switches.
+        let actions = engine.observe(&observe(&browser, "rule-chat", ImeStatus::Original, start, 1.0));
+        assert_eq!(actions, [Action::Switch(ImeStatus::English)]);
+        assert!(engine.manual_override_for(&docs));
+
+        let actions = engine.observe(&observe(&browser, "rule-docs", ImeStatus::Original, start, 2.0));
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn switches_and_announces_the_switch() {
+        let editor = process("editor.exe");
+        let start = Instant::now();
+        let mut engine = Engine::default();
+
+        let actions = engine.observe(&observe(&editor, "rule-1", ImeStatus::Original, start, 0.0));
+        assert_eq!(actions, [Action::Switch(ImeStatus::English)]);
+        let status = engine.switched(true, start).unwrap();
+        assert_eq!(status.key, "toast.status.autoSwitch");
+        assert_eq!(status.values["name"], "editor.exe");
+
+        // Already in the target mode: nothing to do, and a switch that changed nothing is quiet.
+        let actions = engine.observe(&observe(&editor, "rule-1", ImeStatus::English, start, 1.0));
+        assert!(actions.is_empty());
+        assert!(engine.switched(false, start + Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn manual_change_pauses_switching() {
+        let editor = process("editor.exe");
+        let key = OverrideKey::new("rule-1", "editor.exe");
+        let start = Instant::now();
+        let mut engine = Engine::default();
+
+        engine.observe(&observe(&editor, "rule-1", ImeStatus::English, start, 0.0));
+        let actions = engine.observe(&observe(&editor, "rule-1", ImeStatus::Original, start, 1.0));
+        assert_eq!(
+            actions,
+            [Action::UpdateOverride {
+                key: key.clone(),
+                enabled: true
+            }]
+        );
+        engine.set_manual_override(&key, true, start + Duration::from_secs(1), None);
+
+        let actions = engine.observe(&observe(&editor, "rule-1", ImeStatus::Original, start, 2.0));
+        assert!(actions.is_empty());
+        assert!(engine.manual_override_for(&key));
+    }
+
+    #[test]
+    fn focus_change_is_not_a_manual_change() {
+        let editor = process("editor.exe");
+        let chat = process("chat.exe");
+        let start = Instant::now();
+        let mut engine = Engine::default();
+
+        engine.observe(&observe(&editor, "rule-1", ImeStatus::English, start, 0.0));
+        let actions = engine.observe(&observe(&chat, "rule-2", ImeStatus::Original, start, 1.0));
+        assert_eq!(actions, [Action::Switch(ImeStatus::English)]);
+    }
+
+    #[test]
+    fn mouse_movement_resumes_switching() {
+        let editor = process("editor.exe");
+        let key = OverrideKey::new("rule-1", "editor.exe");
+        let start = Instant::now();
+        let mut engine = Engine::default();
+        engine.set_manual_override(&key, true, start, None);
+        let at = |cursor, secs| Observation {
+            cursor: Some(cursor),
+            ..observe(&editor, "rule-1", ImeStatus::Original, start, secs)
+        };
+
+        assert!(engine.observe(&at((0, 0), 1.0)).is_empty());
+        // Below the sensitivity of 10 pixels.
+        assert!(engine.observe(&at((6, 0), 2.0)).is_empty());
+        let actions = engine.observe(&at((30, 0), 3.0));
+        assert_eq!(
+            actions,
+            [
+                Action::UpdateOverride {
+                    key: key.clone(),
+                    enabled: false
+                },
+                Action::Switch(ImeStatus::English),
+            ]
+        );
+        engine.set_manual_override(&key, false, start + Duration::from_secs(3), None);
+        let status = engine.switched(false, start + Duration::from_secs(3)).unwrap();
+        assert_eq!(status.key, "toast.status.mouseMove");
+    }
+
+    #[test]
+    fn repeated_status_waits_for_the_cooldown() {
+        let editor = process("editor.exe");
+        let start = Instant::now();
+        let mut engine = Engine::default();
+        let mut announced = |secs: f32| {
+            let observation = observe(&editor, "rule-1", ImeStatus::Original, start, secs);
+            assert_eq!(engine.observe(&observation), [Action::Switch(ImeStatus::English)]);
+            engine.switched(true, observation.at).is_some()
+        };
+
+        assert!(announced(0.0));
+        assert!(!announced(0.5));
+        assert!(announced(1.5));
+    }
+
+    #[test]
+    fn slow_mouse_movement_adds_up_between_polls() {
+        let editor = process("editor.exe");
+        let key = OverrideKey::new("rule-1", "editor.exe");
+        let start = Instant::now();
+        let mut engine = Engine::default();
+        engine.set_manual_override(&key, true, start, None);
+        let at = |cursor, secs| Observation {
+            cursor: Some(cursor),
+            ..observe(&editor, "rule-1", ImeStatus::Original, start, secs)
+        };
+
+        // However often the cursor is sampled, 12 pixels away from where it rested resumes.
+        assert!(engine.observe(&at((0, 0), 1.0)).is_empty());
+        assert!(engine.observe(&at((4, 0), 1.1)).is_empty());
+        assert!(engine.observe(&at((8, 0), 1.2)).is_empty());
+        let actions = engine.observe(&at((12, 0), 1.3));
+        assert!(actions.contains(&Action::Switch(ImeStatus::English)));
+    }
+}
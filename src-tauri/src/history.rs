@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+
+use crate::config::AppConfig;
+use crate::state::StatusMessage;
+
+const HISTORY_LIMIT: usize = 50;
+
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    description: StatusMessage,
+    config: AppConfig,
+}
+
+/// Bounded undo/redo stacks of draft snapshots. Each entry pairs a snapshot with a description
+/// (an i18n key plus values) of the edit that moves away from it.
+#[derive(Debug, Default)]
+pub struct DraftHistory {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl DraftHistory {
+    /// Records `before`, the draft as it was just before the edit described by `description`.
+    pub fn record(&mut self, description: StatusMessage, before: AppConfig) {
+        self.undo.push_back(HistoryEntry {
+            description,
+            config: before,
+        });
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+    }
+
+    /// Returns the draft to restore and the description of the edit being undone.
+    pub fn undo(&mut self, current: &AppConfig) -> Option<(AppConfig, StatusMessage)> {
+        let entry = self.undo.pop_back()?;
+        self.redo.push(HistoryEntry {
+            description: entry.description.clone(),
+            config: current.clone(),
+        });
+        Some((entry.config, entry.description))
+    }
+
+    pub fn redo(&mut self, current: &AppConfig) -> Option<(AppConfig, StatusMessage)> {
+        let entry = self.redo.pop()?;
+        self.undo.push_back(HistoryEntry {
+            description: entry.description.clone(),
+            config: current.clone(),
+        });
+        Some((entry.config, entry.description))
+    }
+
+    /// The draft [`Self::undo`] would restore, without moving anything between the stacks.
+    pub fn undo_target(&self) -> Option<&AppConfig> {
+        self.undo.back().map(|entry| &entry.config)
+    }
+
+    pub fn redo_target(&self) -> Option<&AppConfig> {
+        self.redo.last().map(|entry| &entry.config)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn undo_description(&self) -> Option<&StatusMessage> {
+        self.undo.back().map(|entry| &entry.description)
+    }
+
+    pub fn redo_description(&self) -> Option<&StatusMessage> {
+        self.redo.last().map(|entry| &entry.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A config told apart by its detect interval.
+    fn config(secs: f32) -> AppConfig {
+        AppConfig {
+            detect_interval_secs: secs,
+            ..AppConfig::default()
+        }
+    }
+
+    fn edit(secs: f32) -> StatusMessage {
+        StatusMessage::with_values("toast.history.detectInterval", [("to", secs)])
+    }
+
+    #[test]
+    fn undo_and_redo_round_trip() {
+        let mut history = DraftHistory::default();
+        history.record(edit(1.0), config(0.5));
+
+        let (restored, description) = history.undo(&config(1.0)).unwrap();
+        assert_eq!(restored.detect_interval_secs, 0.5);
+        assert_eq!(description, edit(1.0));
+        assert!(history.undo_target().is_none());
+        assert_eq!(history.redo_description(), Some(&edit(1.0)));
+
+        let (reapplied, description) = history.redo(&restored).unwrap();
+        assert_eq!(reapplied.detect_interval_secs, 1.0);
+        assert_eq!(description, edit(1.0));
+        assert!(history.redo_target().is_none());
+        assert_eq!(history.undo_target().map(|cfg| cfg.detect_interval_secs), Some(0.5));
+    }
+
+    #[test]
+    fn a_new_edit_clears_redo() {
+        let mut history = DraftHistory::default();
+        history.record(edit(1.0), config(0.5));
+        history.undo(&config(1.0)).unwrap();
+
+        history.record(edit(2.0), config(0.5));
+
+        assert!(history.redo_target().is_none());
+        assert!(history.redo(&config(2.0)).is_none());
+    }
+
+    #[test]
+    fn only_the_latest_edits_are_kept() {
+        let mut history = DraftHistory::default();
+        for step in 0..HISTORY_LIMIT + 10 {
+            history.record(edit(step as f32 + 1.0), config(step as f32));
+        }
+
+        let mut current = config((HISTORY_LIMIT + 10) as f32);
+        let mut undone = 0;
+        while let Some((previous, _)) = history.undo(&current) {
+            current = previous;
+            undone += 1;
+        }
+        assert_eq!(undone, HISTORY_LIMIT);
+        assert_eq!(current.detect_interval_secs, 10.0);
+    }
+}
@@ -1,19 +1,7 @@
-use std::thread;
-use std::time::Duration;
-
-use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
-use windows::Win32::UI::Input::Ime::ImmGetDefaultIMEWnd;
-use windows::Win32::UI::Input::KeyboardAndMouse::{
-    INPUT, INPUT_0, INPUT_KEYBOARD, KEYBD_EVENT_FLAGS, KEYBDINPUT, KEYEVENTF_KEYUP, SendInput,
-    VIRTUAL_KEY,
-};
-use windows::Win32::UI::WindowsAndMessaging::SendMessageW;
-
-const WM_IME_CONTROL: u32 = 0x0283;
-const IMC_GETCONVERSIONMODE_PARAM: usize = 0x0005;
-const VK_HANGUL: u16 = 0x15;
+
+#[cfg(windows)]
+pub use win32::{ensure_status, ime_status, toggle_hangul_key};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -26,93 +14,114 @@ pub enum ImeStatus {
     Unknown,
 }
 
-pub fn ime_status(hwnd: HWND) -> Result<ImeStatus> {
-    if hwnd.0.is_null() {
-        return Ok(ImeStatus::Unknown);
-    }
+#[cfg(windows)]
+mod win32 {
+    use std::thread;
+    use std::time::Duration;
 
-    let ime_hwnd = unsafe { ImmGetDefaultIMEWnd(hwnd) };
-    if ime_hwnd.0.is_null() {
-        return Ok(ImeStatus::Unknown);
-    }
-
-    let result = unsafe {
-        SendMessageW(
-            ime_hwnd,
-            WM_IME_CONTROL,
-            WPARAM(IMC_GETCONVERSIONMODE_PARAM),
-            LPARAM(0),
-        )
+    use anyhow::{Context, Result, anyhow};
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::UI::Input::Ime::ImmGetDefaultIMEWnd;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        INPUT, INPUT_0, INPUT_KEYBOARD, KEYBD_EVENT_FLAGS, KEYBDINPUT, KEYEVENTF_KEYUP, SendInput,
+        VIRTUAL_KEY,
     };
+    use windows::Win32::UI::WindowsAndMessaging::SendMessageW;
 
-    if result == LRESULT(0) {
-        Ok(ImeStatus::English)
-    } else {
-        Ok(ImeStatus::Original)
-    }
-}
+    use super::ImeStatus;
+
+    const WM_IME_CONTROL: u32 = 0x0283;
+    const IMC_GETCONVERSIONMODE_PARAM: usize = 0x0005;
+    const VK_HANGUL: u16 = 0x15;
+
+    pub fn ime_status(hwnd: HWND) -> Result<ImeStatus> {
+        if hwnd.0.is_null() {
+            return Ok(ImeStatus::Unknown);
+        }
+
+        let ime_hwnd = unsafe { ImmGetDefaultIMEWnd(hwnd) };
+        if ime_hwnd.0.is_null() {
+            return Ok(ImeStatus::Unknown);
+        }
+
+        let result = unsafe {
+            SendMessageW(
+                ime_hwnd,
+                WM_IME_CONTROL,
+                WPARAM(IMC_GETCONVERSIONMODE_PARAM),
+                LPARAM(0),
+            )
+        };
 
-pub fn ensure_english(hwnd: HWND) -> Result<bool> {
-    if hwnd.0.is_null() {
-        return Ok(false);
+        if result == LRESULT(0) {
+            Ok(ImeStatus::English)
+        } else {
+            Ok(ImeStatus::Original)
+        }
     }
 
-    let mut toggled = false;
+    pub fn ensure_status(hwnd: HWND, target: ImeStatus) -> Result<bool> {
+        if hwnd.0.is_null() || target == ImeStatus::Unknown {
+            return Ok(false);
+        }
+
+        let mut toggled = false;
 
-    for _ in 0..3 {
-        match ime_status(hwnd)? {
-            ImeStatus::English => return Ok(toggled),
-            ImeStatus::Original | ImeStatus::Unknown => {
-                toggle_hangul_key().context("IME 토글 시뮬레이션 실패")?;
-                toggled = true;
-                thread::sleep(Duration::from_millis(80));
-
-                match ime_status(hwnd)? {
-                    ImeStatus::English => return Ok(true),
-                    ImeStatus::Original | ImeStatus::Unknown => continue,
-                }
+        for _ in 0..3 {
+            if ime_status(hwnd)? == target {
+                return Ok(toggled);
+            }
+
+            toggle_hangul_key().context("IME 토글 시뮬레이션 실패")?;
+            toggled = true;
+            thread::sleep(Duration::from_millis(80));
+
+            if ime_status(hwnd)? == target {
+                return Ok(true);
             }
         }
-    }
 
-    match ime_status(hwnd)? {
-        ImeStatus::English => Ok(true),
-        _ if toggled => Err(anyhow!("IME 토글 후에도 영문 전환 확인에 실패했습니다.")),
-        _ => Ok(false),
+        match ime_status(hwnd)? {
+            status if status == target => Ok(true),
+            _ if toggled => Err(anyhow!(
+                "IME 토글 후에도 입력 모드 전환 확인에 실패했습니다."
+            )),
+            _ => Ok(false),
+        }
     }
-}
 
-pub fn toggle_hangul_key() -> Result<()> {
-    unsafe {
-        let press = INPUT {
-            r#type: INPUT_KEYBOARD,
-            Anonymous: INPUT_0 {
-                ki: KEYBDINPUT {
-                    wVk: VIRTUAL_KEY(VK_HANGUL),
-                    wScan: 0,
-                    dwFlags: KEYBD_EVENT_FLAGS(0),
-                    time: 0,
-                    dwExtraInfo: 0,
+    pub fn toggle_hangul_key() -> Result<()> {
+        unsafe {
+            let press = INPUT {
+                r#type: INPUT_KEYBOARD,
+                Anonymous: INPUT_0 {
+                    ki: KEYBDINPUT {
+                        wVk: VIRTUAL_KEY(VK_HANGUL),
+                        wScan: 0,
+                        dwFlags: KEYBD_EVENT_FLAGS(0),
+                        time: 0,
+                        dwExtraInfo: 0,
+                    },
                 },
-            },
-        };
-        let release = INPUT {
-            r#type: INPUT_KEYBOARD,
-            Anonymous: INPUT_0 {
-                ki: KEYBDINPUT {
-                    wVk: VIRTUAL_KEY(VK_HANGUL),
-                    wScan: 0,
-                    dwFlags: KEYEVENTF_KEYUP,
-                    time: 0,
-                    dwExtraInfo: 0,
+            };
+            let release = INPUT {
+                r#type: INPUT_KEYBOARD,
+                Anonymous: INPUT_0 {
+                    ki: KEYBDINPUT {
+                        wVk: VIRTUAL_KEY(VK_HANGUL),
+                        wScan: 0,
+                        dwFlags: KEYEVENTF_KEYUP,
+                        time: 0,
+                        dwExtraInfo: 0,
+                    },
                 },
-            },
-        };
-        let inputs = [press, release];
-        let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
-        if sent != inputs.len() as u32 {
-            return Err(anyhow!("SendInput 실패: {}", sent));
+            };
+            let inputs = [press, release];
+            let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+            if sent != inputs.len() as u32 {
+                return Err(anyhow!("SendInput 실패: {}", sent));
+            }
         }
+        Ok(())
     }
-    Ok(())
 }
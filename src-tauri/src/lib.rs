@@ -1,15 +1,33 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(not(target_os = "windows"), not(test)))]
 compile_error!("Langcon은 Windows 전용 애플리케이션입니다.");
 
+mod bundle;
+mod cli;
 mod config;
+mod engine;
+mod history;
 mod ime;
+mod migration;
 mod monitor;
+mod pattern;
+mod platform;
+mod poller;
 mod process;
+mod process_list;
+mod profiles;
+mod recent;
+mod resolver;
+mod rules;
 mod state;
 mod startup;
+mod storage;
+mod trace;
+mod validation;
+mod watcher;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Result, anyhow};
 use parking_lot::Mutex;
@@ -31,19 +49,30 @@ use tauri::{
 };
 use tauri_plugin_notification::NotificationExt;
 
-use crate::config::{ConfigManager, WindowState};
-use crate::ime::{ImeStatus, ime_status, toggle_hangul_key};
+use crate::bundle::{BundleScope, ImportMode, ImportPreview, SettingsBundle};
+use crate::config::{ConfigLocation, ConfigManager, WindowState};
+use crate::engine::OverrideKey;
 use crate::monitor::Monitor;
-use crate::process::ActiveWindowInfo;
-use crate::state::{AppViewModel, FocusSnapshot, FocusSnapshotInternal, SharedAppState};
+use crate::platform::{InputMethod, NativePlatform, WindowSystem};
+use crate::process::ProcessQuery;
+use crate::recent::RecentAppEntry;
+use crate::resolver::{Evaluation, RuleTestResult};
+use crate::rules::{ProcessRule, ResumePolicy};
+use crate::state::{AppViewModel, FocusSnapshot, FocusSnapshotInternal, SharedAppState, StatusMessage};
+use crate::watcher::ConfigWatcher;
 use crate::config::{FALLBACK_LANGUAGE, sanitize_language};
 
 const TRAY_MENU_SHOW: &str = "tray-show";
 const TRAY_MENU_QUIT: &str = "tray-quit";
 const TRAY_MENU_RESET_WINDOW: &str = "tray-reset-window";
+const TRAY_MENU_PAUSE: &str = "tray-pause";
+
+/// How long the tray's pause entry keeps an app paused.
+const TRAY_PAUSE_MINUTES: u64 = 15;
 
 struct TrayText {
     open: &'static str,
+    pause: String,
     reset_window: &'static str,
     quit: &'static str,
     running: &'static str,
@@ -53,24 +82,28 @@ fn tray_texts(language: &str) -> TrayText {
     match language {
         "ko" => TrayText {
             open: "창 열기",
+            pause: format!("현재 앱 {TRAY_PAUSE_MINUTES}분 동안 일시 중지"),
             reset_window: "창 위치/크기 초기화",
             quit: "종료",
             running: "Langcon이 트레이에서 실행 중입니다.",
         },
         "ja" => TrayText {
             open: "ウィンドウを開く",
+            pause: format!("現在のアプリを{TRAY_PAUSE_MINUTES}分間一時停止"),
             reset_window: "ウィンドウ位置/サイズをリセット",
             quit: "終了",
             running: "Langcon がトレイで実行中です。",
         },
         "zh" => TrayText {
             open: "打开窗口",
+            pause: format!("暂停当前应用 {TRAY_PAUSE_MINUTES} 分钟"),
             reset_window: "重置窗口位置/大小",
             quit: "退出",
             running: "Langcon 正在托盘中运行。",
         },
         _ => TrayText {
             open: "Open window",
+            pause: format!("Pause current app for {TRAY_PAUSE_MINUTES} minutes"),
             reset_window: "Reset window position/size",
             quit: "Quit",
             running: "Langcon is running in the tray.",
@@ -95,17 +128,23 @@ fn notify_tray_running(app: &AppHandle, language: &str) {
 struct AppContext {
     state: SharedAppState,
     monitor: Mutex<Monitor>,
+    watcher: Mutex<ConfigWatcher>,
     handle: AppHandle,
     config_manager: Arc<ConfigManager>,
+    platform: Arc<NativePlatform>,
 }
 
 impl AppContext {
     fn initialize(app: &AppHandle) -> Result<Self> {
-        let (config_manager, config) = ConfigManager::load_or_create()?;
+        let (config_manager, config, issues) = ConfigManager::load_or_create()?;
+        for issue in &issues {
+            tracing::warn!(issue = %issue.describe(), "config 검증 결과");
+        }
         let config_manager = Arc::new(config_manager);
         let state = Arc::new(Mutex::new(crate::state::AppState::new(
             config_manager.clone(),
             config,
+            issues,
         )));
 
         {
@@ -119,13 +158,17 @@ impl AppContext {
             }
         }
 
-        let monitor = Monitor::start(app.clone(), state.clone());
+        let platform = Arc::new(NativePlatform::default());
+        let monitor = Monitor::start(app.clone(), state.clone(), platform.clone());
+        let watcher = ConfigWatcher::start(app.clone(), state.clone(), config_manager.clone());
 
         Ok(Self {
             state,
             monitor: Mutex::new(monitor),
+            watcher: Mutex::new(watcher),
             handle: app.clone(),
             config_manager,
+            platform,
         })
     }
 
@@ -141,6 +184,7 @@ impl AppContext {
 impl Drop for AppContext {
     fn drop(&mut self) {
         self.monitor.lock().stop();
+        self.watcher.lock().stop();
     }
 }
 
@@ -166,6 +210,34 @@ fn discard_changes(app_state: State<AppContext>) -> Result<AppViewModel, String>
     Ok(guard.to_view_model())
 }
 
+#[tauri::command]
+fn undo(app_state: State<AppContext>) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    let description = guard.undo().map_err(|err| err.to_string())?;
+    let mut view_model = guard.to_view_model();
+    view_model.status_message = description;
+    Ok(view_model)
+}
+
+#[tauri::command]
+fn redo(app_state: State<AppContext>) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    let description = guard.redo().map_err(|err| err.to_string())?;
+    let mut view_model = guard.to_view_model();
+    view_model.status_message = description;
+    Ok(view_model)
+}
+
+#[tauri::command]
+fn resolve_config_conflict(
+    app_state: State<AppContext>,
+    accept_external: bool,
+) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard.resolve_external_conflict(accept_external);
+    Ok(guard.to_view_model())
+}
+
 #[tauri::command]
 fn set_use_auto_to_en(app_state: State<AppContext>, enabled: bool) -> Result<AppViewModel, String> {
     let mut guard = app_state.state.lock();
@@ -196,6 +268,24 @@ fn set_detect_interval(app_state: State<AppContext>, seconds: f32) -> Result<App
     Ok(guard.to_view_model())
 }
 
+#[tauri::command]
+fn set_max_detect_interval(app_state: State<AppContext>, seconds: f32) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .set_max_detect_interval(seconds)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn set_process_auto_refresh(app_state: State<AppContext>, seconds: f32) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .set_process_auto_refresh(seconds)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
 #[tauri::command]
 fn set_mouse_sensitivity(app_state: State<AppContext>, distance: f32) -> Result<AppViewModel, String> {
     let mut guard = app_state.state.lock();
@@ -205,6 +295,18 @@ fn set_mouse_sensitivity(app_state: State<AppContext>, distance: f32) -> Result<
     Ok(guard.to_view_model())
 }
 
+#[tauri::command]
+fn set_resume_policies(
+    app_state: State<AppContext>,
+    policies: Vec<ResumePolicy>,
+) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .set_resume_policies(policies)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
 #[tauri::command]
 fn set_start_with_windows(app_state: State<AppContext>, enabled: bool) -> Result<AppViewModel, String> {
     let mut guard = app_state.state.lock();
@@ -233,15 +335,212 @@ fn remove_selected_process(app_state: State<AppContext>, name: String) -> Result
 }
 
 #[tauri::command]
-fn refresh_processes(app_state: State<AppContext>) -> Result<AppViewModel, String> {
-    match crate::process::enumerate_gui_processes() {
+fn add_ignored_process(app_state: State<AppContext>, pattern: String) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .add_ignored_process(&pattern)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn remove_ignored_process(app_state: State<AppContext>, pattern: String) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .remove_ignored_process(&pattern)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn reset_ignored_processes(app_state: State<AppContext>) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .reset_ignored_processes()
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn add_process_rule(app_state: State<AppContext>, rule: ProcessRule) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .add_process_rule(rule)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn update_process_rule(app_state: State<AppContext>, rule: ProcessRule) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .update_process_rule(rule)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn remove_process_rule(app_state: State<AppContext>, id: String) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .remove_process_rule(&id)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn export_settings(app_state: State<AppContext>, path: String, processes_only: bool) -> Result<(), String> {
+    let scope = if processes_only {
+        BundleScope::ProcessesOnly
+    } else {
+        BundleScope::Full
+    };
+    let bundle = {
+        let guard = app_state.state.lock();
+        guard.export_bundle(scope).map_err(|err| err.to_string())?
+    };
+    bundle
+        .write(std::path::Path::new(&path))
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn preview_import(
+    app_state: State<AppContext>,
+    path: String,
+    mode: ImportMode,
+) -> Result<ImportPreview, String> {
+    let bundle = SettingsBundle::read(std::path::Path::new(&path)).map_err(|err| err.to_string())?;
+    let guard = app_state.state.lock();
+    guard
+        .preview_import(&bundle, mode)
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn import_settings(
+    app_state: State<AppContext>,
+    path: String,
+    mode: ImportMode,
+) -> Result<AppViewModel, String> {
+    let bundle = SettingsBundle::read(std::path::Path::new(&path)).map_err(|err| err.to_string())?;
+    let mut guard = app_state.state.lock();
+    guard
+        .apply_import(&bundle, mode)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn create_profile(app_state: State<AppContext>, name: String) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard.create_profile(&name).map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn rename_profile(
+    app_state: State<AppContext>,
+    old_name: String,
+    new_name: String,
+) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .rename_profile(&old_name, &new_name)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn duplicate_profile(
+    app_state: State<AppContext>,
+    name: String,
+    new_name: String,
+) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .duplicate_profile(&name, &new_name)
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn delete_profile(app_state: State<AppContext>, name: String) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard.delete_profile(&name).map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn activate_profile(app_state: State<AppContext>, name: String) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard.activate_profile(&name).map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn test_rule(
+    app_state: State<AppContext>,
+    process_name: String,
+    path: Option<String>,
+    title: Option<String>,
+) -> Result<RuleTestResult, String> {
+    let guard = app_state.state.lock();
+    Ok(guard.test_rule(&process_name, path.as_deref(), title.as_deref()))
+}
+
+#[tauri::command]
+fn get_recent_apps(app_state: State<AppContext>) -> Result<Vec<RecentAppEntry>, String> {
+    Ok(app_state.state.lock().recent_apps())
+}
+
+#[tauri::command]
+fn clear_recent_apps(app_state: State<AppContext>) -> Result<(), String> {
+    app_state
+        .state
+        .lock()
+        .clear_recent_apps()
+        .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn start_trace_recording(
+    app_state: State<AppContext>,
+    file_name: Option<String>,
+) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard
+        .start_trace(file_name.as_deref())
+        .map_err(|err| err.to_string())?;
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn stop_trace_recording(app_state: State<AppContext>) -> Result<AppViewModel, String> {
+    let mut guard = app_state.state.lock();
+    guard.stop_trace();
+    Ok(guard.to_view_model())
+}
+
+#[tauri::command]
+fn refresh_processes(
+    app_state: State<AppContext>,
+    query: Option<ProcessQuery>,
+) -> Result<AppViewModel, String> {
+    let ignored = {
+        let mut guard = app_state.state.lock();
+        if let Some(query) = query {
+            let update = guard.set_process_query(query);
+            let _ = app_state.handle.emit("processes-updated", update);
+        }
+        guard.active_config().ignored_processes.clone()
+    };
+    match app_state.platform.applications(&ignored) {
         Ok(list) => {
-            {
-                let mut guard = app_state.state.lock();
-                guard.set_available_processes(list.clone());
-            }
-            let _ = app_state.handle.emit("processes-updated", list);
             let mut guard = app_state.state.lock();
+            if let Some(update) = guard.set_available_processes(list) {
+                let _ = app_state.handle.emit("processes-updated", update);
+            }
             Ok(guard.to_view_model())
         }
         Err(err) => Err(err.to_string()),
@@ -250,43 +549,65 @@ fn refresh_processes(app_state: State<AppContext>) -> Result<AppViewModel, Strin
 
 #[tauri::command]
 fn toggle_ime(app_state: State<AppContext>) -> Result<FocusSnapshot, String> {
-    let active = active_window()?
+    let platform = app_state.platform.as_ref();
+    let active = platform
+        .focused_window()
+        .map_err(|err| err.to_string())?
         .ok_or_else(|| "활성 창을 찾을 수 없습니다.".to_string())?;
 
-    toggle_hangul_key().map_err(|err| err.to_string())?;
-    let ime = ime_status(active.hwnd).unwrap_or(ImeStatus::Unknown);
-    let snapshot = FocusSnapshotInternal {
-        process: Some(active.process.clone()),
-        ime_status: ime,
-        manual_override: false,
-        updated_at: chrono::Local::now(),
-    };
-
-    {
+    platform.toggle().map_err(|err| err.to_string())?;
+    let ime = platform.status(active.handle);
+    let rules = app_state.state.lock().rules();
+    let Evaluation {
+        decision,
+        chain,
+        resolution: resolved,
+    } = rules.evaluate(&active.process, || platform.ancestors(&active.process));
+    let snapshot = {
         let mut guard = app_state.state.lock();
+        let snapshot = FocusSnapshotInternal {
+            process: Some(active.process.clone()),
+            ime_status: ime,
+            manual_override: false,
+            manual_override_expires_at: None,
+            matched_rule: resolved.as_ref().map(|resolution| resolution.rule.clone()),
+            matched_via_parent: resolved.and_then(|resolution| resolution.via_parent),
+            decision,
+            rule_chain: chain,
+            updated_at: chrono::Local::now(),
+        };
         guard.set_focus(Some(snapshot.clone()));
-    }
-
-    let payload = FocusSnapshot {
-        process: snapshot.process,
-        ime_status: snapshot.ime_status,
-        manual_override: snapshot.manual_override,
-        updated_at: Some(snapshot.updated_at.format("%H:%M:%S").to_string()),
+        snapshot
     };
 
+    let payload = FocusSnapshot::from(&snapshot);
+
     let _ = app_state.handle.emit("focus-changed", payload.clone());
 
     Ok(payload)
 }
 
+/// Pauses `process_name` while `rule_id` applies to it. `duration_secs` keeps the app paused
+/// for that long, whatever its input mode, then resumes automatic switching; without it the
+/// override lasts until a resume policy ends it.
 #[tauri::command]
 fn set_manual_override(
     app_state: State<AppContext>,
+    rule_id: String,
     process_name: String,
     enabled: bool,
+    duration_secs: Option<f32>,
 ) -> Result<AppViewModel, String> {
+    let duration = duration_secs
+        .map(|secs| {
+            Duration::try_from_secs_f32(secs)
+                .ok()
+                .filter(|duration| !duration.is_zero())
+                .ok_or_else(|| format!("유지 시간이 올바르지 않습니다: {secs}"))
+        })
+        .transpose()?;
     let mut guard = app_state.state.lock();
-    guard.set_manual_override(&process_name, enabled);
+    guard.set_manual_override(&OverrideKey::new(rule_id, process_name), enabled, duration);
     Ok(guard.to_view_model())
 }
 
@@ -303,6 +624,11 @@ fn set_language(app_state: State<AppContext>, language: String) -> Result<AppVie
     Ok(guard.to_view_model())
 }
 
+#[tauri::command]
+fn get_config_location(app_state: State<AppContext>) -> ConfigLocation {
+    app_state.config_manager.location().clone()
+}
+
 #[tauri::command]
 fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
@@ -347,6 +673,10 @@ async fn get_latest_version() -> Result<String, String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    if let Some(code) = crate::cli::run_from_args() {
+        std::process::exit(code);
+    }
+
     if let Err(err) = init_tracing() {
         eprintln!("로거 초기화 실패: {err}");
     }
@@ -411,17 +741,43 @@ pub fn run() {
             load_state,
             save_changes,
             discard_changes,
+            undo,
+            redo,
+            resolve_config_conflict,
             set_use_auto_to_en,
             set_use_mouse_move_event,
             set_detect_interval,
+            set_max_detect_interval,
             set_mouse_sensitivity,
+            set_resume_policies,
+            set_process_auto_refresh,
             set_start_with_windows,
             add_selected_process,
             remove_selected_process,
+            add_process_rule,
+            update_process_rule,
+            remove_process_rule,
+            add_ignored_process,
+            remove_ignored_process,
+            reset_ignored_processes,
+            export_settings,
+            preview_import,
+            import_settings,
+            create_profile,
+            rename_profile,
+            duplicate_profile,
+            delete_profile,
+            activate_profile,
             refresh_processes,
+            start_trace_recording,
+            stop_trace_recording,
+            test_rule,
+            get_recent_apps,
+            clear_recent_apps,
             toggle_ime,
             set_manual_override,
             set_language,
+            get_config_location,
             get_app_version,
             get_latest_version,
         ])
@@ -449,10 +805,6 @@ fn current_language(app: &AppHandle) -> String {
     FALLBACK_LANGUAGE.to_string()
 }
 
-fn active_window() -> std::result::Result<Option<ActiveWindowInfo>, String> {
-    crate::process::active_window_info().map_err(|err| err.to_string())
-}
-
 fn persist_window_state(app: &AppHandle, window: &WebviewWindow) -> Result<()> {
     let position = window
         .outer_position()
@@ -508,6 +860,9 @@ fn build_tray_menu<R: Runtime, M: Manager<R>>(app: &M, texts: &TrayText) -> taur
     let show_item = MenuItemBuilder::new(texts.open)
         .id(TRAY_MENU_SHOW)
         .build(app)?;
+    let pause_item = MenuItemBuilder::new(&texts.pause)
+        .id(TRAY_MENU_PAUSE)
+        .build(app)?;
     let reset_window_item = MenuItemBuilder::new(texts.reset_window)
         .id(TRAY_MENU_RESET_WINDOW)
         .build(app)?;
@@ -517,6 +872,7 @@ fn build_tray_menu<R: Runtime, M: Manager<R>>(app: &M, texts: &TrayText) -> taur
 
     MenuBuilder::new(app)
         .item(&show_item)
+        .item(&pause_item)
         .separator()
         .item(&reset_window_item)
         .separator()
@@ -534,6 +890,7 @@ fn setup_tray(app: &mut tauri::App, language: &str) -> tauri::Result<TrayIcon> {
         .show_menu_on_left_click(false)
         .on_menu_event(|app, event| match event.id().as_ref() {
             TRAY_MENU_SHOW => show_main_window(app),
+            TRAY_MENU_PAUSE => pause_from_tray(app),
             TRAY_MENU_RESET_WINDOW => {
                 if let Err(err) = reset_window(app) {
                     tracing::warn!(?err, "창 위치/크기 초기화에 실패했습니다");
@@ -582,6 +939,27 @@ fn apply_tray_language(app_handle: &AppHandle, language: &str) {
     }
 }
 
+/// Pauses the app that had focus before the tray menu was opened, for
+/// [`TRAY_PAUSE_MINUTES`].
+fn pause_from_tray(app: &AppHandle) {
+    let Some(context) = app.try_state::<AppContext>() else {
+        return;
+    };
+    let duration = Duration::from_secs(TRAY_PAUSE_MINUTES * 60);
+    let paused = context.state.lock().pause_last_focused(duration);
+    let message = match paused {
+        Some(name) => StatusMessage::with_values(
+            "toast.status.pausedFromTray",
+            [
+                ("name", serde_json::Value::from(name)),
+                ("minutes", serde_json::Value::from(TRAY_PAUSE_MINUTES)),
+            ],
+        ),
+        None => StatusMessage::new("toast.status.nothingToPause"),
+    };
+    let _ = app.emit("status-message", message);
+}
+
 fn show_main_window(app: &AppHandle) {
     if let Some(window) = app.get_webview_window("main") {
         if let Err(err) = window.show() {
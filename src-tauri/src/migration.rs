@@ -0,0 +1,229 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Map, Value};
+
+/// Schema version written into every `config.json` produced by this build.
+pub const CONFIG_SCHEMA_VERSION: u32 = 4;
+
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+type MigrationFn = fn(&mut Map<String, Value>) -> Result<()>;
+
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: MigrationFn,
+}
+
+/// Ordered upgrade steps. Entry `n` upgrades a file from version `from` to `from + 1`.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "레거시 키 이름을 현재 이름으로 변경",
+        apply: migrate_v0_to_v1,
+    },
+    Migration {
+        from: 1,
+        description: "selected_processes를 process_rules로 변환",
+        apply: migrate_v1_to_v2,
+    },
+    Migration {
+        from: 2,
+        description: "기존 설정을 Default 프로필로 지정",
+        apply: migrate_v2_to_v3,
+    },
+    Migration {
+        from: 3,
+        description: "최대 감지 간격을 기존 감지 간격으로 지정",
+        apply: migrate_v3_to_v4,
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationOutcome {
+    pub from: u32,
+    pub to: u32,
+}
+
+impl MigrationOutcome {
+    /// Whether the file was upgraded and should be written back.
+    pub fn changed(&self) -> bool {
+        self.from < self.to
+    }
+
+    /// The file comes from a newer build; it must not be rewritten in this build's schema.
+    pub fn is_from_newer_build(&self) -> bool {
+        self.from > self.to
+    }
+}
+
+/// Reads the schema version of a raw config. Files written before versioning are version 0.
+pub fn schema_version_of(raw: &Value) -> u32 {
+    raw.get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Upgrades `raw` in place to [`CONFIG_SCHEMA_VERSION`].
+///
+/// Files from a newer build are not transformed. Unknown fields are dropped by serde on load,
+/// so callers must not write such a config back, see [`MigrationOutcome::is_from_newer_build`].
+pub fn migrate(raw: &mut Value) -> Result<MigrationOutcome> {
+    let from = schema_version_of(raw);
+    let object = raw
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("config 최상위 값이 객체가 아닙니다"))?;
+
+    if from > CONFIG_SCHEMA_VERSION {
+        tracing::warn!(
+            from,
+            current = CONFIG_SCHEMA_VERSION,
+            "더 최신 버전에서 저장된 config입니다. 읽기 전용으로 불러옵니다"
+        );
+        return Ok(MigrationOutcome { from, to: CONFIG_SCHEMA_VERSION });
+    }
+
+    let mut version = from;
+    for step in MIGRATIONS.iter().skip_while(|step| step.from < from) {
+        if step.from != version {
+            return Err(anyhow!("마이그레이션 체인이 끊겼습니다: v{} 단계가 없습니다", version));
+        }
+        (step.apply)(object)
+            .with_context(|| format!("config 마이그레이션 실패 (v{} → v{})", step.from, step.from + 1))?;
+        version = step.from + 1;
+        object.insert(SCHEMA_VERSION_KEY.to_string(), Value::from(version));
+        tracing::info!(from = step.from, to = version, step = step.description, "config 마이그레이션 적용");
+    }
+
+    Ok(MigrationOutcome { from, to: version })
+}
+
+/// Copies `path` into `backup_dir` as `config.v{version}.{timestamp}.json` before it is rewritten.
+pub fn backup_before_migration(path: &Path, backup_dir: &Path, version: u32) -> Result<PathBuf> {
+    fs::create_dir_all(backup_dir).context("백업 디렉터리 생성 실패")?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let target = backup_dir.join(format!("config.v{version}.{stamp}.json"));
+    fs::copy(path, &target).context("마이그레이션 전 config 백업 실패")?;
+    Ok(target)
+}
+
+fn migrate_v0_to_v1(object: &mut Map<String, Value>) -> Result<()> {
+    const RENAMES: [(&str, &str); 4] = [
+        ("selected_process_list", "selected_processes"),
+        ("use_auto_org_to_en", "use_auto_to_en"),
+        ("detect_process_language_interval", "detect_interval_secs"),
+        ("detect_mose_movement_sensitivity", "mouse_sensitivity"),
+    ];
+
+    for (old, new) in RENAMES {
+        if let Some(value) = object.remove(old) {
+            object.entry(new).or_insert(value);
+        }
+    }
+    Ok(())
+}
+
+fn migrate_v1_to_v2(object: &mut Map<String, Value>) -> Result<()> {
+    let names = match object.remove("selected_processes") {
+        Some(Value::Array(names)) => names,
+        Some(Value::Null) | None => Vec::new(),
+        Some(other) => return Err(anyhow!("selected_processes 형식이 올바르지 않습니다: {other}")),
+    };
+
+    let mut rules: Vec<Value> = match object.remove("process_rules") {
+        Some(Value::Array(rules)) => rules,
+        _ => Vec::new(),
+    };
+    for name in names.iter().filter_map(Value::as_str) {
+        let id = format!("rule-{}", rules.len() + 1);
+        rules.push(serde_json::json!({
+            "id": id,
+            "processName": name,
+            "enabled": true,
+            "targetMode": "english",
+        }));
+    }
+    object.insert("process_rules".to_string(), Value::Array(rules));
+    Ok(())
+}
+
+fn migrate_v2_to_v3(object: &mut Map<String, Value>) -> Result<()> {
+    // The existing top-level settings become the active profile; there are no others yet.
+    object
+        .entry("active_profile")
+        .or_insert_with(|| Value::from(crate::profiles::DEFAULT_PROFILE_NAME));
+    object
+        .entry("profiles")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    Ok(())
+}
+
+fn migrate_v3_to_v4(object: &mut Map<String, Value>) -> Result<()> {
+    // Files written before adaptive polling keep their fixed interval; backing off is opt-in.
+    fn keep_fixed_interval(settings: &mut Map<String, Value>) {
+        if let Some(interval) = settings.get("detect_interval_secs").cloned() {
+            settings.entry("max_detect_interval_secs").or_insert(interval);
+        }
+    }
+
+    keep_fixed_interval(object);
+    if let Some(Value::Array(profiles)) = object.get_mut("profiles") {
+        for profile in profiles.iter_mut().filter_map(Value::as_object_mut) {
+            keep_fixed_interval(profile);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn upgrades_legacy_config_to_current_schema() {
+        let mut raw = json!({
+            "use_auto_org_to_en": false,
+            "selected_process_list": ["javaw.exe"],
+        });
+        let outcome = migrate(&mut raw).unwrap();
+
+        assert!(outcome.changed());
+        assert_eq!(schema_version_of(&raw), CONFIG_SCHEMA_VERSION);
+        assert_eq!(raw["use_auto_to_en"], json!(false));
+        assert_eq!(raw["process_rules"][0]["processName"], json!("javaw.exe"));
+        assert_eq!(raw["active_profile"], json!(crate::profiles::DEFAULT_PROFILE_NAME));
+    }
+
+    #[test]
+    fn leaves_newer_schema_untouched() {
+        let original = json!({ "schema_version": CONFIG_SCHEMA_VERSION + 1, "future": true });
+        let mut raw = original.clone();
+        let outcome = migrate(&mut raw).unwrap();
+
+        assert!(!outcome.changed());
+        assert!(outcome.is_from_newer_build());
+        assert_eq!(raw, original);
+    }
+
+    #[test]
+    fn existing_intervals_stay_fixed() {
+        let mut raw = json!({
+            "schema_version": 3,
+            "detect_interval_secs": 0.2,
+            "profiles": [
+                { "name": "Work", "detect_interval_secs": 1.0 },
+                { "name": "Game", "detect_interval_secs": 0.5, "max_detect_interval_secs": 3.0 },
+            ],
+        });
+        migrate(&mut raw).unwrap();
+
+        assert_eq!(raw["max_detect_interval_secs"], json!(0.2));
+        assert_eq!(raw["profiles"][0]["max_detect_interval_secs"], json!(1.0));
+        assert_eq!(raw["profiles"][1]["max_detect_interval_secs"], json!(3.0));
+    }
+}
@@ -1,32 +1,34 @@
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use chrono::Local;
 use tauri::{AppHandle, Emitter};
-use windows::Win32::Foundation::POINT;
-use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
 
-use crate::ime::{ImeStatus, ensure_english, ime_status};
-use crate::process::{active_window_info, enumerate_gui_processes};
+use crate::engine::{Action, Observation, OverrideKey};
+use crate::ime::ImeStatus;
+use crate::platform::{InputMethod, WindowSystem};
+use crate::poller::{Activity, AdaptivePoller};
+use crate::process_list::ProcessListUpdate;
+use crate::resolver::Evaluation;
 use crate::state::{FocusSnapshot, FocusSnapshotInternal, SharedAppState, StatusMessage};
 
 pub struct Monitor {
-    shutdown: Arc<AtomicBool>,
+    shutdown: Sender<()>,
     handle: Option<thread::JoinHandle<()>>,
 }
 
-const STATUS_COOLDOWN_MS: i64 = 1000;
-
 impl Monitor {
-    pub fn start(app: AppHandle, state: SharedAppState) -> Self {
-        let shutdown = Arc::new(AtomicBool::new(false));
-        let thread_shutdown = shutdown.clone();
+    pub fn start<P>(app: AppHandle, state: SharedAppState, platform: Arc<P>) -> Self
+    where
+        P: WindowSystem + InputMethod + Send + Sync + 'static,
+    {
+        let (shutdown, shutdown_rx) = mpsc::channel();
 
         let handle = thread::spawn(move || {
-            if let Err(err) = run_loop(app, state, thread_shutdown) {
+            if let Err(err) = run_loop(app, state, platform.as_ref(), shutdown_rx) {
                 tracing::error!(?err, "모니터링 스레드가 예외로 종료되었습니다");
             }
         });
@@ -37,191 +39,346 @@ impl Monitor {
         }
     }
 
+    /// Wakes the loop from its wait, so this returns without waiting out the interval.
     pub fn stop(&mut self) {
-        self.shutdown.store(true, Ordering::Relaxed);
+        let _ = self.shutdown.send(());
         if let Some(handle) = self.handle.take() {
             let _ = handle.join();
         }
     }
 }
 
-fn run_loop(app: AppHandle, state: SharedAppState, shutdown: Arc<AtomicBool>) -> Result<()> {
-    while !shutdown.load(Ordering::Relaxed) {
-        let (
-            interval,
-            use_auto_to_en,
-            use_mouse_move,
-            sensitivity,
-            selected_processes,
-            refresh_requested,
-            last_cursor,
-        ) = {
+/// Events produced by one [`tick`], for the caller to emit.
+#[derive(Debug, Default)]
+pub struct TickEvents {
+    pub processes: Option<ProcessListUpdate>,
+    /// `Some(None)` when nothing has focus.
+    pub focus: Option<Option<FocusSnapshot>>,
+    pub status: Option<StatusMessage>,
+}
+
+fn run_loop<P>(
+    app: AppHandle,
+    state: SharedAppState,
+    platform: &P,
+    shutdown: Receiver<()>,
+) -> Result<()>
+where
+    P: WindowSystem + InputMethod,
+{
+    let mut poller = AdaptivePoller::default();
+    let mut reported_interval = None;
+    loop {
+        let events = tick(&state, platform);
+        if let Some(update) = events.processes {
+            let _ = app.emit("processes-updated", update);
+        }
+        if let Some(focus) = events.focus {
+            let _ = app.emit("focus-changed", focus);
+        }
+        if let Some(message) = events.status {
+            let _ = app.emit("status-message", message);
+        }
+
+        let interval = {
             let mut guard = state.lock();
             let cfg = guard.active_config();
-            let interval = cfg.detect_interval_secs.max(0.1);
-            let use_auto = cfg.use_auto_to_en;
-            let use_mouse = cfg.use_mouse_move_event;
-            let sensitivity = cfg.mouse_sensitivity;
-            let selected = cfg.selected_processes.clone();
-            let refresh_requested = guard.take_process_refresh_request();
-            let last_cursor = guard.last_cursor_pos;
-            (
-                interval,
-                use_auto,
-                use_mouse,
-                sensitivity,
-                selected,
-                refresh_requested,
-                last_cursor,
-            )
+            let (min, max) = (cfg.detect_interval_secs.max(0.1), cfg.max_detect_interval_secs);
+            let interval = poller.next_interval(Activity::of(guard.focus.as_ref()), min, max);
+            guard.set_effective_detect_interval(interval);
+            interval
         };
-
-        if refresh_requested {
-            match enumerate_gui_processes() {
-                Ok(list) => {
-                    let mut guard = state.lock();
-                    guard.set_available_processes(list.clone());
-                    let _ = app.emit("processes-updated", list);
-                }
-                Err(err) => tracing::warn!(?err, "GUI 프로세스 목록을 가져오는 중 오류"),
-            }
+        if reported_interval.replace(interval) != Some(interval) {
+            let _ = app.emit("detect-interval-changed", interval);
         }
+        // A stop request (or a dropped `Monitor`) ends the wait early.
+        let wait = shutdown.recv_timeout(Duration::from_secs_f32(interval));
+        if wait != Err(RecvTimeoutError::Timeout) {
+            break;
+        }
+    }
 
-        match active_window_info() {
-            Ok(Some(active)) => {
-                let ime = ime_status(active.hwnd).unwrap_or(ImeStatus::Unknown);
-
-                let (prev_snapshot, manual_override_active) = {
-                    let guard = state.lock();
-                    (
-                        guard.focus.clone(),
-                        guard.manual_override_for(&active.process.name),
-                    )
-                };
-
-                let mut manual_change = manual_override_active;
-                let mut status_message: Option<StatusMessage> = None;
-                let mut should_switch = false;
-                let process_selected = selected_processes.contains(&active.process.name);
-
-                if process_selected {
-                    if let Some(prev) = prev_snapshot {
-                        if let Some(prev_proc) = prev.process {
-                            if prev_proc.name == active.process.name
-                                && prev.ime_status == ImeStatus::English
-                                && ime == ImeStatus::Original
-                            {
-                                manual_change = true;
-                            }
-                        }
-                    }
+    platform.shutdown();
+    state.lock().save_recent_apps(true);
+    Ok(())
+}
 
-                    if manual_change && ime == ImeStatus::English {
-                        manual_change = false;
-                    }
+/// One pass of the monitor: refreshes the process list when due, observes the focused
+/// window and carries out what the engine decides.
+pub fn tick<P>(state: &SharedAppState, platform: &P) -> TickEvents
+where
+    P: WindowSystem + InputMethod,
+{
+    let mut events = TickEvents::default();
+    let (use_auto_to_en, refresh_requested, ignored) = {
+        let mut guard = state.lock();
+        let cfg = guard.active_config();
+        let use_auto = cfg.use_auto_to_en;
+        let ignored = cfg.ignored_processes.clone();
+        let refresh_requested = guard.take_process_refresh_request();
+        (use_auto, refresh_requested, ignored)
+    };
 
-                    if use_auto_to_en
-                        && matches!(ime, ImeStatus::Original | ImeStatus::Unknown)
-                        && !manual_change
-                    {
-                        should_switch = true;
-                    }
-                }
+    if refresh_requested {
+        match platform.applications(&ignored) {
+            Ok(list) => events.processes = state.lock().set_available_processes(list),
+            Err(err) => tracing::warn!(?err, "GUI 프로세스 목록을 가져오는 중 오류"),
+        }
+    }
 
-                let mut new_cursor = last_cursor;
-                if process_selected && use_mouse_move {
-                    if let Some(position) = current_cursor_pos() {
-                        new_cursor = Some(position);
-                        if let Some(prev) = last_cursor {
-                            if distance(prev, position) >= sensitivity {
-                                manual_change = false;
-                                if matches!(ime, ImeStatus::Original | ImeStatus::Unknown) {
-                                    should_switch = true;
-                                    status_message = Some(StatusMessage::with_values(
-                                        "toast.status.mouseMove",
-                                        [("name", active.process.name.clone())],
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
+    match platform.focused_window() {
+        Ok(Some(active)) => {
+            let ime = platform.status(active.handle);
+            let rules = state.lock().rules();
+            let Evaluation {
+                decision,
+                chain,
+                resolution: resolved,
+            } = rules.evaluate(&active.process, || platform.ancestors(&active.process));
 
-                if should_switch {
-                    match ensure_english(active.hwnd) {
-                        Ok(toggled) => {
-                            if toggled {
-                                status_message = Some(StatusMessage::with_values(
-                                    "toast.status.autoSwitch",
-                                    [("name", active.process.name.clone())],
-                                ));
+            let actions = state.lock().observe(&Observation {
+                process: Some(&active.process),
+                settings: resolved.as_ref().map(|resolution| resolution.settings),
+                rule_id: resolved.as_ref().map(|resolution| resolution.rule.id.as_str()),
+                ime,
+                cursor: platform.cursor_position(),
+                // Only a keyboard-idle policy needs it, and asking installs a global hook.
+                keyboard_idle: resolved
+                    .as_ref()
+                    .is_some_and(|resolution| {
+                        resolution.settings.resume_after_keyboard_idle_secs.is_some()
+                    })
+                    .then(|| platform.keyboard_idle())
+                    .flatten(),
+                use_auto_to_en,
+                at: Instant::now(),
+            });
+            for action in actions {
+                match action {
+                    Action::Switch(target) => {
+                        let toggled = match platform.ensure(active.handle, target) {
+                            Ok(toggled) => toggled,
+                            Err(err) => {
+                                tracing::warn!(
+                                    ?err,
+                                    process = %active.process.name,
+                                    "영문 전환 실패"
+                                );
+                                false
                             }
-                        }
-                        Err(err) => {
-                            tracing::warn!(?err, process = %active.process.name, "영문 전환 실패");
-                        }
-                    }
-                }
-
-                {
-                    let mut guard = state.lock();
-                    guard.set_manual_override(&active.process.name, manual_change);
-                    guard.set_focus(Some(FocusSnapshotInternal {
-                        process: Some(active.process.clone()),
-                        ime_status: ime,
-                        manual_override: manual_change,
-                        updated_at: Local::now(),
-                    }));
-                    guard.last_cursor_pos = new_cursor;
-                    let mut message_to_emit: Option<StatusMessage> = None;
-                    if let Some(message) = status_message.clone() {
-                        if guard.should_emit_status(&message, STATUS_COOLDOWN_MS) {
-                            guard.record_status(message.clone());
-                            message_to_emit = Some(message);
-                        }
-                    }
-                    if let Some(snapshot) = guard.focus.as_ref() {
-                        let _ = app.emit(
-                            "focus-changed",
-                            FocusSnapshot {
-                                process: snapshot.process.clone(),
-                                ime_status: snapshot.ime_status,
-                                manual_override: snapshot.manual_override,
-                                updated_at: Some(snapshot.updated_at.format("%H:%M:%S").to_string()),
-                            },
-                        );
+                        };
+                        events.status = state.lock().switched(toggled, Instant::now());
                     }
-                    drop(guard);
-                    if let Some(message) = message_to_emit {
-                        let _ = app.emit("status-message", message);
+                    Action::EmitStatus(message) => events.status = Some(message),
+                    Action::UpdateOverride { key, enabled } => {
+                        state.lock().set_manual_override(&key, enabled, None)
                     }
                 }
             }
-            Ok(None) => {
-                let mut guard = state.lock();
-                guard.set_focus(None);
-                let _ = app.emit::<Option<FocusSnapshot>>("focus-changed", None);
+
+            let mut guard = state.lock();
+            guard.record_focus(Some(&active.process));
+            let key = resolved
+                .as_ref()
+                .map(|resolution| OverrideKey::new(&resolution.rule.id, &active.process.name));
+            let manual_override = key.as_ref().is_some_and(|key| guard.manual_override_for(key));
+            let manual_override_expires_at = key.and_then(|key| guard.manual_override_expiry(&key));
+            guard.set_focus(Some(FocusSnapshotInternal {
+                process: Some(active.process.clone()),
+                ime_status: ime,
+                manual_override,
+                manual_override_expires_at,
+                matched_rule: resolved.as_ref().map(|resolution| resolution.rule.clone()),
+                matched_via_parent: resolved.and_then(|resolution| resolution.via_parent),
+                decision,
+                rule_chain: chain,
+                updated_at: Local::now(),
+            }));
+            events.focus = Some(guard.focus.as_ref().map(FocusSnapshot::from));
+        }
+        Ok(None) => {
+            let mut guard = state.lock();
+            guard.observe(&Observation {
+                process: None,
+                settings: None,
+                rule_id: None,
+                ime: ImeStatus::Unknown,
+                cursor: None,
+                keyboard_idle: None,
+                use_auto_to_en,
+                at: Instant::now(),
+            });
+            guard.record_focus(None);
+            guard.set_focus(None);
+            events.focus = Some(None);
+        }
+        Err(err) => tracing::warn!(?err, "활성 창 정보를 가져오는 중 오류"),
+    }
+    state.lock().save_recent_apps(false);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::config::{ConfigDirSource, ConfigLocation, ConfigManager};
+    use crate::platform::{FocusedWindow, WindowHandle};
+    use crate::process::{AppEntry, ProcessInfo};
+    use crate::rules::ResumePolicy;
+    use crate::state::AppState;
+
+    /// One window of `editor.exe` whose input mode and cursor the test moves by hand.
+    struct FakePlatform {
+        ime: Cell<ImeStatus>,
+        cursor: Cell<(i32, i32)>,
+        switches: Cell<usize>,
+        keyboard_queries: Cell<usize>,
+    }
+
+    impl FakePlatform {
+        fn new(ime: ImeStatus) -> Self {
+            Self {
+                ime: Cell::new(ime),
+                cursor: Cell::new((0, 0)),
+                switches: Cell::new(0),
+                keyboard_queries: Cell::new(0),
             }
-            Err(err) => tracing::warn!(?err, "활성 창 정보를 가져오는 중 오류"),
+        }
+    }
+
+    impl WindowSystem for FakePlatform {
+        fn focused_window(&self) -> Result<Option<FocusedWindow>> {
+            Ok(Some(FocusedWindow {
+                handle: WindowHandle(1),
+                process: ProcessInfo {
+                    pid: 42,
+                    name: "editor.exe".into(),
+                    ..ProcessInfo::default()
+                },
+            }))
         }
 
-        thread::sleep(Duration::from_secs_f32(interval));
+        fn ancestors(&self, _process: &ProcessInfo) -> Vec<ProcessInfo> {
+            Vec::new()
+        }
+
+        fn applications(&self, _ignored: &[String]) -> Result<Vec<AppEntry>> {
+            Ok(Vec::new())
+        }
+
+        fn cursor_position(&self) -> Option<(i32, i32)> {
+            Some(self.cursor.get())
+        }
+
+        fn keyboard_idle(&self) -> Option<Duration> {
+            self.keyboard_queries.set(self.keyboard_queries.get() + 1);
+            None
+        }
     }
 
-    Ok(())
-}
+    impl InputMethod for FakePlatform {
+        fn status(&self, _window: WindowHandle) -> ImeStatus {
+            self.ime.get()
+        }
+
+        fn ensure(&self, _window: WindowHandle, target: ImeStatus) -> Result<bool> {
+            let toggled = self.ime.replace(target) != target;
+            if toggled {
+                self.switches.set(self.switches.get() + 1);
+            }
+            Ok(toggled)
+        }
+
+        fn toggle(&self) -> Result<()> {
+            let next = match self.ime.get() {
+                ImeStatus::English => ImeStatus::Original,
+                _ => ImeStatus::English,
+            };
+            self.ime.set(next);
+            Ok(())
+        }
+    }
 
-fn current_cursor_pos() -> Option<(i32, i32)> {
-    let mut point = POINT::default();
-    if unsafe { GetCursorPos(&mut point) }.is_ok() {
-        Some((point.x, point.y))
-    } else {
-        None
+    /// Saved config with a rule for `editor.exe`, switching to English on mouse movement too.
+    fn test_state() -> (tempfile::TempDir, SharedAppState) {
+        let dir = tempfile::tempdir().unwrap();
+        let location = ConfigLocation {
+            dir: dir.path().to_path_buf(),
+            source: ConfigDirSource::CliFlag,
+        };
+        let (manager, config, issues) = ConfigManager::load_or_create_in(location).unwrap();
+        let mut state = AppState::new(Arc::new(manager), config, issues);
+        state.add_selected_process("editor.exe").unwrap();
+        state.set_use_auto_to_en(true).unwrap();
+        state.set_use_mouse_move_event(true).unwrap();
+        state.save_changes().unwrap();
+        (dir, Arc::new(Mutex::new(state)))
+    }
+
+    fn focus(events: &TickEvents) -> &FocusSnapshot {
+        events.focus.as_ref().unwrap().as_ref().unwrap()
+    }
+
+    #[test]
+    fn switches_the_focused_window_and_reports_it() {
+        let (_dir, state) = test_state();
+        let platform = FakePlatform::new(ImeStatus::Original);
+
+        let events = tick(&state, &platform);
+        assert_eq!(platform.ime.get(), ImeStatus::English);
+        let focus = focus(&events);
+        assert_eq!(focus.ime_status, ImeStatus::Original);
+        assert!(!focus.manual_override);
+        assert!(focus.matched_rule.is_some());
+        assert_eq!(events.status.unwrap().key, "toast.status.autoSwitch");
+
+        let events = tick(&state, &platform);
+        assert!(events.status.is_none());
+        assert_eq!(platform.switches.get(), 1);
     }
-}
 
-fn distance(a: (i32, i32), b: (i32, i32)) -> f32 {
-    let dx = (a.0 - b.0) as f32;
-    let dy = (a.1 - b.1) as f32;
-    (dx * dx + dy * dy).sqrt()
+    #[test]
+    fn manual_change_holds_until_the_mouse_moves() {
+        let (_dir, state) = test_state();
+        let platform = FakePlatform::new(ImeStatus::English);
+        tick(&state, &platform);
+
+        platform.toggle().unwrap();
+        let events = tick(&state, &platform);
+        assert!(focus(&events).manual_override);
+        let events = tick(&state, &platform);
+        assert!(focus(&events).manual_override);
+        assert_eq!(platform.ime.get(), ImeStatus::Original);
+        assert_eq!(platform.switches.get(), 0);
+
+        platform.cursor.set((200, 150));
+        let events = tick(&state, &platform);
+        assert!(!focus(&events).manual_override);
+        assert_eq!(platform.ime.get(), ImeStatus::English);
+        assert_eq!(events.status.unwrap().key, "toast.status.autoSwitch");
+    }
+
+    #[test]
+    fn the_keyboard_is_only_watched_for_a_keyboard_idle_policy() {
+        let (_dir, state) = test_state();
+        let platform = FakePlatform::new(ImeStatus::English);
+
+        tick(&state, &platform);
+        assert_eq!(platform.keyboard_queries.get(), 0);
+
+        {
+            let mut guard = state.lock();
+            guard
+                .set_resume_policies(vec![ResumePolicy::KeyboardIdle { secs: 5.0 }])
+                .unwrap();
+            guard.save_changes().unwrap();
+        }
+        tick(&state, &platform);
+        assert_eq!(platform.keyboard_queries.get(), 1);
+    }
 }
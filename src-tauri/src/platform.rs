@@ -0,0 +1,284 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::ime::ImeStatus;
+use crate::process::{AppEntry, ProcessInfo};
+
+/// Opaque identity of a top-level window, only meaningful to the platform that handed it out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WindowHandle(pub isize);
+
+pub struct FocusedWindow {
+    pub handle: WindowHandle,
+    pub process: ProcessInfo,
+}
+
+/// What the monitor needs to know about windows and processes.
+pub trait WindowSystem {
+    fn focused_window(&self) -> Result<Option<FocusedWindow>>;
+
+    /// Parent chain of `process`, nearest first.
+    fn ancestors(&self, process: &ProcessInfo) -> Vec<ProcessInfo>;
+
+    /// Applications with a visible window, skipping names that match `ignored`.
+    fn applications(&self, ignored: &[String]) -> Result<Vec<AppEntry>>;
+
+    fn cursor_position(&self) -> Option<(i32, i32)>;
+
+    /// Time since the last key press, `None` when it cannot be told.
+    fn keyboard_idle(&self) -> Option<Duration>;
+
+    /// Releases what was set up on demand, such as the keyboard hook. Called once the monitor
+    /// has stopped.
+    fn shutdown(&self) {}
+}
+
+/// Reading and switching the input mode of a window.
+pub trait InputMethod {
+    fn status(&self, window: WindowHandle) -> ImeStatus;
+
+    /// Switches `window` to `target`; returns whether a toggle was needed.
+    fn ensure(&self, window: WindowHandle, target: ImeStatus) -> Result<bool>;
+
+    /// Toggles whatever window has focus.
+    fn toggle(&self) -> Result<()>;
+}
+
+#[cfg(windows)]
+pub type NativePlatform = Win32;
+#[cfg(not(windows))]
+pub type NativePlatform = Unsupported;
+
+#[cfg(windows)]
+pub use win32::Win32;
+
+#[cfg(windows)]
+mod win32 {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::mpsc;
+    use std::thread::{self, JoinHandle};
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use parking_lot::Mutex;
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetCursorPos, GetMessageW, HC_ACTION, KBDLLHOOKSTRUCT, LLKHF_INJECTED, MSG,
+        PM_NOREMOVE, PeekMessageW, PostThreadMessageW, SetWindowsHookExW, UnhookWindowsHookEx,
+        WH_KEYBOARD_LL, WM_KEYDOWN, WM_QUIT, WM_SYSKEYDOWN, WM_USER,
+    };
+
+    use super::{FocusedWindow, InputMethod, WindowHandle, WindowSystem};
+    use crate::ime::{self, ImeStatus};
+    use crate::process::{self, AppEntry, ProcessInfo};
+
+    #[derive(Debug, Default)]
+    pub struct Win32 {
+        keyboard_hook: Mutex<HookState>,
+    }
+
+    /// `GetTickCount` of the last key pressed on a keyboard, stamped by [`keyboard_proc`].
+    static LAST_KEY_TICK: AtomicU32 = AtomicU32::new(0);
+
+    /// The low-level keyboard hook, installed the first time a keyboard-idle policy needs it.
+    #[derive(Debug, Default)]
+    enum HookState {
+        #[default]
+        NotInstalled,
+        Running(KeyboardHook),
+        /// Installing failed or the platform was shut down; not tried again.
+        Unavailable,
+    }
+
+    /// A `WH_KEYBOARD_LL` hook on a thread of its own, which has to pump messages for the
+    /// hook to be called.
+    #[derive(Debug)]
+    struct KeyboardHook {
+        thread_id: u32,
+        thread: JoinHandle<()>,
+    }
+
+    impl KeyboardHook {
+        fn install() -> Option<Self> {
+            let (sender, receiver) = mpsc::channel();
+            let thread = thread::Builder::new()
+                .name("keyboard-hook".into())
+                .spawn(move || {
+                    LAST_KEY_TICK.store(unsafe { GetTickCount() }, Ordering::Relaxed);
+                    // Creates the message queue, so `WM_QUIT` can be posted as soon as the
+                    // thread id is out.
+                    let mut message = MSG::default();
+                    let _ =
+                        unsafe { PeekMessageW(&mut message, None, WM_USER, WM_USER, PM_NOREMOVE) };
+                    let hook = unsafe {
+                        GetModuleHandleW(None).and_then(|module| {
+                            SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), module, 0)
+                        })
+                    };
+                    let hook = match hook {
+                        Ok(hook) => hook,
+                        Err(err) => {
+                            tracing::warn!(?err, "키보드 훅을 설치할 수 없습니다");
+                            let _ = sender.send(None);
+                            return;
+                        }
+                    };
+                    let _ = sender.send(Some(unsafe { GetCurrentThreadId() }));
+                    while unsafe { GetMessageW(&mut message, None, 0, 0) }.0 > 0 {}
+                    if let Err(err) = unsafe { UnhookWindowsHookEx(hook) } {
+                        tracing::warn!(?err, "키보드 훅을 해제할 수 없습니다");
+                    }
+                })
+                .ok()?;
+            let thread_id = receiver.recv().ok().flatten()?;
+            Some(Self { thread_id, thread })
+        }
+
+        /// Ends the message loop, which unhooks before the thread exits.
+        fn remove(self) {
+            let posted = unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) };
+            if let Err(err) = posted {
+                tracing::warn!(?err, "키보드 훅 스레드를 종료할 수 없습니다");
+                return;
+            }
+            let _ = self.thread.join();
+        }
+    }
+
+    impl Win32 {
+        /// Installs the hook on first use. Returns whether it is running.
+        fn keyboard_hook_installed(&self) -> bool {
+            let mut state = self.keyboard_hook.lock();
+            if matches!(*state, HookState::NotInstalled) {
+                *state = KeyboardHook::install()
+                    .map_or(HookState::Unavailable, HookState::Running);
+            }
+            matches!(*state, HookState::Running(_))
+        }
+    }
+
+    /// Keys sent by programs, such as [`ime::toggle_hangul_key`], are not typing.
+    unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        let pressed = matches!(wparam.0 as u32, WM_KEYDOWN | WM_SYSKEYDOWN);
+        if code == HC_ACTION as i32 && pressed {
+            let event = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
+            if (event.flags & LLKHF_INJECTED).0 == 0 {
+                LAST_KEY_TICK.store(event.time, Ordering::Relaxed);
+            }
+        }
+        unsafe { CallNextHookEx(None, code, wparam, lparam) }
+    }
+
+    impl From<HWND> for WindowHandle {
+        fn from(hwnd: HWND) -> Self {
+            Self(hwnd.0 as isize)
+        }
+    }
+
+    impl From<WindowHandle> for HWND {
+        fn from(handle: WindowHandle) -> Self {
+            HWND(handle.0 as *mut _)
+        }
+    }
+
+    impl WindowSystem for Win32 {
+        fn focused_window(&self) -> Result<Option<FocusedWindow>> {
+            process::focused_window()
+        }
+
+        fn ancestors(&self, child: &ProcessInfo) -> Vec<ProcessInfo> {
+            process::ancestors_of(child)
+        }
+
+        fn applications(&self, ignored: &[String]) -> Result<Vec<AppEntry>> {
+            process::enumerate_gui_processes(ignored)
+        }
+
+        fn cursor_position(&self) -> Option<(i32, i32)> {
+            let mut point = POINT::default();
+            unsafe { GetCursorPos(&mut point) }
+                .is_ok()
+                .then_some((point.x, point.y))
+        }
+
+        /// Counted from when the hook was installed until the first key press.
+        fn keyboard_idle(&self) -> Option<Duration> {
+            if !self.keyboard_hook_installed() {
+                return None;
+            }
+            let last_key = LAST_KEY_TICK.load(Ordering::Relaxed);
+            let since_key = unsafe { GetTickCount() }.wrapping_sub(last_key);
+            Some(Duration::from_millis(since_key.into()))
+        }
+
+        fn shutdown(&self) {
+            let mut state = self.keyboard_hook.lock();
+            if let HookState::Running(hook) = std::mem::replace(&mut *state, HookState::Unavailable)
+            {
+                hook.remove();
+            }
+        }
+    }
+
+    impl InputMethod for Win32 {
+        fn status(&self, window: WindowHandle) -> ImeStatus {
+            ime::ime_status(window.into()).unwrap_or(ImeStatus::Unknown)
+        }
+
+        fn ensure(&self, window: WindowHandle, target: ImeStatus) -> Result<bool> {
+            ime::ensure_status(window.into(), target)
+        }
+
+        fn toggle(&self) -> Result<()> {
+            ime::toggle_hangul_key()
+        }
+    }
+}
+
+/// Stands in for the Win32 platform elsewhere so the crate still builds for tests; nothing
+/// ever has focus and the input mode cannot be changed.
+#[cfg(not(windows))]
+#[derive(Debug, Default)]
+pub struct Unsupported;
+
+#[cfg(not(windows))]
+impl WindowSystem for Unsupported {
+    fn focused_window(&self) -> Result<Option<FocusedWindow>> {
+        Ok(None)
+    }
+
+    fn ancestors(&self, process: &ProcessInfo) -> Vec<ProcessInfo> {
+        crate::process::ancestors_of(process)
+    }
+
+    fn applications(&self, _ignored: &[String]) -> Result<Vec<AppEntry>> {
+        Ok(Vec::new())
+    }
+
+    fn cursor_position(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    fn keyboard_idle(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(not(windows))]
+impl InputMethod for Unsupported {
+    fn status(&self, _window: WindowHandle) -> ImeStatus {
+        ImeStatus::Unknown
+    }
+
+    fn ensure(&self, _window: WindowHandle, _target: ImeStatus) -> Result<bool> {
+        anyhow::bail!("입력기 전환은 Windows에서만 지원합니다")
+    }
+
+    fn toggle(&self) -> Result<()> {
+        anyhow::bail!("입력기 전환은 Windows에서만 지원합니다")
+    }
+}
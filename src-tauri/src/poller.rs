@@ -0,0 +1,109 @@
+use crate::ime::ImeStatus;
+use crate::state::FocusSnapshotInternal;
+
+/// Each unchanged tick stretches the interval by this factor, up to the configured maximum.
+const BACKOFF_FACTOR: f32 = 1.5;
+
+/// What has to stay the same for the poller to back off: the focused window and its input mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    pid: Option<u32>,
+    title: String,
+    ime: ImeStatus,
+}
+
+impl Activity {
+    pub fn of(focus: Option<&FocusSnapshotInternal>) -> Option<Self> {
+        let focus = focus?;
+        Some(Self {
+            pid: focus.process.as_ref().map(|process| process.pid),
+            title: focus
+                .process
+                .as_ref()
+                .map(|process| process.title.clone())
+                .unwrap_or_default(),
+            ime: focus.ime_status,
+        })
+    }
+}
+
+/// Picks how long the monitor sleeps between ticks: `min_secs` right after the focus or the
+/// input mode changes, then longer and longer while nothing happens.
+#[derive(Debug, Default)]
+pub struct AdaptivePoller {
+    last: Option<Option<Activity>>,
+    interval_secs: f32,
+}
+
+impl AdaptivePoller {
+    /// Seconds to wait before the next tick, given what this tick saw.
+    pub fn next_interval(
+        &mut self,
+        activity: Option<Activity>,
+        min_secs: f32,
+        max_secs: f32,
+    ) -> f32 {
+        let max_secs = max_secs.max(min_secs);
+        let changed = self.last.as_ref() != Some(&activity);
+        self.last = Some(activity);
+        self.interval_secs = if changed {
+            min_secs
+        } else {
+            (self.interval_secs * BACKOFF_FACTOR).clamp(min_secs, max_secs)
+        };
+        self.interval_secs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(pid: u32) -> Option<Activity> {
+        Some(Activity {
+            pid: Some(pid),
+            title: "Untitled".into(),
+            ime: ImeStatus::English,
+        })
+    }
+
+    #[test]
+    fn backs_off_while_nothing_changes() {
+        let mut poller = AdaptivePoller::default();
+        assert_eq!(poller.next_interval(activity(1), 0.5, 2.0), 0.5);
+        assert_eq!(poller.next_interval(activity(1), 0.5, 2.0), 0.75);
+        assert_eq!(poller.next_interval(activity(1), 0.5, 2.0), 1.125);
+    }
+
+    #[test]
+    fn a_change_resets_to_the_minimum() {
+        let mut poller = AdaptivePoller::default();
+        for _ in 0..5 {
+            poller.next_interval(activity(1), 0.5, 2.0);
+        }
+        assert_eq!(poller.next_interval(activity(2), 0.5, 2.0), 0.5);
+        assert_eq!(poller.next_interval(None, 0.5, 2.0), 0.5);
+
+        let mut switched = activity(2);
+        if let Some(activity) = switched.as_mut() {
+            activity.ime = ImeStatus::Original;
+        }
+        poller.next_interval(activity(2), 0.5, 2.0);
+        assert_eq!(poller.next_interval(switched, 0.5, 2.0), 0.5);
+    }
+
+    #[test]
+    fn stays_within_the_configured_bounds() {
+        let mut poller = AdaptivePoller::default();
+        for _ in 0..10 {
+            poller.next_interval(activity(1), 0.5, 2.0);
+        }
+        assert_eq!(poller.next_interval(activity(1), 0.5, 2.0), 2.0);
+        // A lowered maximum applies right away.
+        assert_eq!(poller.next_interval(activity(1), 0.5, 1.0), 1.0);
+        // A maximum below the minimum means a fixed interval.
+        assert_eq!(poller.next_interval(activity(1), 0.5, 0.2), 0.5);
+        // So does the default, where both are the same.
+        assert_eq!(poller.next_interval(activity(1), 0.5, 0.5), 0.5);
+    }
+}
@@ -1,17 +1,25 @@
-use std::collections::HashSet;
+// Grouping helpers are only reached through window enumeration, which is Windows-only.
+#![cfg_attr(not(windows), allow(dead_code))]
 
-use anyhow::{Context, Result, anyhow};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, anyhow};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use sysinfo::{Pid, System};
-use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
-use windows::Win32::UI::WindowsAndMessaging::{
-    EnumWindows, GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
-    IsWindowVisible,
-};
-
-const BANNED_PROCESSES: &[&str] = &[
+use sysinfo::{Pid, Process, ProcessRefreshKind, System, UpdateKind};
+
+#[cfg(windows)]
+use win32::file_description;
+#[cfg(windows)]
+pub use win32::{enumerate_gui_processes, focused_window};
+
+const MAX_ANCESTOR_DEPTH: usize = 16;
+const ANCESTOR_CACHE_LIMIT: usize = 64;
+
+/// Seed for `AppConfig::ignored_processes`; shell and overlay windows nobody wants to pick.
+pub const DEFAULT_IGNORED_PROCESSES: &[&str] = &[
     "flet.exe",
     "explorer.exe",
     "textinputhost.exe",
@@ -20,132 +28,575 @@ const BANNED_PROCESSES: &[&str] = &[
     "applicationframehost.exe",
 ];
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub title: String,
+    /// Full executable path, when the process can be queried.
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub command_line: Option<String>,
+    /// Seconds since the Unix epoch.
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+    /// Percent of one core since the previous refresh of this process, so the first sample
+    /// of a new process reads 0.
+    #[serde(default)]
+    pub cpu_usage: Option<f32>,
+    /// `FileDescription` from the executable's version resource, e.g. "Google Chrome".
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+/// A top-level window with a title.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowInfo {
+    pub pid: u32,
+    pub title: String,
+    pub visible: bool,
 }
 
-pub struct ActiveWindowInfo {
-    pub hwnd: HWND,
+/// One application in the process picker: every running instance of an executable and
+/// their windows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppEntry {
+    /// The first instance found, with the title of the first visible window. Memory and CPU
+    /// are summed over all instances and the start time is the earliest one.
+    #[serde(flatten)]
     pub process: ProcessInfo,
+    /// Visible windows first, then in Z order.
+    pub windows: Vec<WindowInfo>,
+    pub instance_count: usize,
 }
 
-pub fn enumerate_gui_processes() -> Result<Vec<ProcessInfo>> {
-    let mut collector = ProcessCollector::default();
-    unsafe {
-        EnumWindows(
-            Some(enum_windows_proc),
-            LPARAM(&mut collector as *mut _ as isize),
-        )
-        .ok()
-        .context("EnumWindows 호출 실패")?;
+impl AppEntry {
+    fn new(process: ProcessInfo) -> Self {
+        Self {
+            process,
+            windows: Vec::new(),
+            instance_count: 1,
+        }
     }
-    Ok(collector.finish())
-}
 
-pub fn active_window_info() -> Result<Option<ActiveWindowInfo>> {
-    let hwnd = unsafe { GetForegroundWindow() };
-    if hwnd.0.is_null() {
-        return Ok(None);
+    /// See [`ProcessInfo::key`].
+    pub fn key(&self) -> String {
+        self.process.key()
     }
 
-    let mut pid = 0u32;
-    unsafe {
-        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+    fn add_instance(&mut self, other: &ProcessInfo) {
+        let process = &mut self.process;
+        self.instance_count += 1;
+        process.memory_bytes = sum_known(process.memory_bytes, other.memory_bytes);
+        process.cpu_usage = sum_known(process.cpu_usage, other.cpu_usage);
+        process.start_time = match (process.start_time, other.start_time) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
     }
-    if pid == 0 {
-        return Ok(None);
+
+    fn push_window(&mut self, window: WindowInfo) {
+        if window.visible && !self.windows.iter().any(|existing| existing.visible) {
+            self.process.title = window.title.clone();
+        }
+        self.windows.push(window);
     }
 
-    let process_name = process_name_for_pid(pid).context("프로세스 이름을 가져오지 못했습니다")?;
-    let title = window_title(hwnd).unwrap_or_default();
+    /// `needle` must already be lowercase.
+    fn contains(&self, needle: &str) -> bool {
+        self.process.contains(needle)
+            || self
+                .windows
+                .iter()
+                .any(|window| window.title.to_lowercase().contains(needle))
+    }
+}
 
-    Ok(Some(ActiveWindowInfo {
-        hwnd,
-        process: ProcessInfo { pid, name: process_name, title },
-    }))
+fn sum_known<T: std::ops::Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
 }
 
-#[derive(Default)]
-struct ProcessCollector {
-    entries: Vec<ProcessInfo>,
-    seen_names: HashSet<String>,
+/// Field the process picker is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessSortKey {
+    #[default]
+    Name,
+    DisplayName,
+    Pid,
+    StartTime,
+    Memory,
+    Cpu,
 }
 
-impl ProcessCollector {
-    fn push(&mut self, info: ProcessInfo) {
-        let key = info.name.to_lowercase();
-        if self.seen_names.insert(key) {
-            self.entries.push(info);
-        }
+/// Sorting and filtering the picker applies to the enumerated processes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProcessQuery {
+    /// Case-insensitive substring of the name, display name, path or any window title.
+    pub filter: Option<String>,
+    pub sort_by: ProcessSortKey,
+    pub descending: bool,
+}
+
+impl ProcessQuery {
+    pub fn apply(&self, apps: &[AppEntry]) -> Vec<AppEntry> {
+        let needle = self
+            .filter
+            .as_deref()
+            .map(|filter| filter.trim().to_lowercase())
+            .filter(|filter| !filter.is_empty());
+        let mut result: Vec<AppEntry> = apps
+            .iter()
+            .filter(|app| needle.as_deref().is_none_or(|needle| app.contains(needle)))
+            .cloned()
+            .collect();
+        // Unknown values sort last either way; ties fall back to the name.
+        result.sort_by(|a, b| {
+            let (a, b) = (&a.process, &b.process);
+            let ordering = match self.sort_by {
+                ProcessSortKey::Name => Some(compare_names(&a.name, &b.name)),
+                ProcessSortKey::DisplayName => a
+                    .display_name
+                    .as_deref()
+                    .zip(b.display_name.as_deref())
+                    .map(|(a, b)| compare_names(a, b)),
+                ProcessSortKey::Pid => Some(a.pid.cmp(&b.pid)),
+                ProcessSortKey::StartTime => a.start_time.zip(b.start_time).map(|(a, b)| a.cmp(&b)),
+                ProcessSortKey::Memory => a.memory_bytes.zip(b.memory_bytes).map(|(a, b)| a.cmp(&b)),
+                ProcessSortKey::Cpu => a.cpu_usage.zip(b.cpu_usage).map(|(a, b)| a.total_cmp(&b)),
+            };
+            let ordering = match ordering {
+                Some(ordering) if self.descending => ordering.reverse(),
+                Some(ordering) => ordering,
+                None => self.sort_value_missing(a).cmp(&self.sort_value_missing(b)),
+            };
+            ordering.then_with(|| compare_names(&a.name, &b.name))
+        });
+        result
     }
 
-    fn finish(mut self) -> Vec<ProcessInfo> {
-        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
-        self.entries
+    fn sort_value_missing(&self, process: &ProcessInfo) -> bool {
+        match self.sort_by {
+            ProcessSortKey::Name | ProcessSortKey::Pid => false,
+            ProcessSortKey::DisplayName => process.display_name.is_none(),
+            ProcessSortKey::StartTime => process.start_time.is_none(),
+            ProcessSortKey::Memory => process.memory_bytes.is_none(),
+            ProcessSortKey::Cpu => process.cpu_usage.is_none(),
+        }
     }
 }
 
-unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
-    let collector = unsafe { &mut *(lparam.0 as *mut ProcessCollector) };
-
-    if unsafe { IsWindowVisible(hwnd) }.as_bool() {
-        let text_len = unsafe { GetWindowTextLengthW(hwnd) };
-        if text_len > 0 {
-            let mut pid = 0u32;
-            unsafe {
-                GetWindowThreadProcessId(hwnd, Some(&mut pid));
-            }
-            if pid != 0 {
-                if let Ok(name) = process_name_for_pid(pid) {
-                    if is_banned(&name) {
-                        return BOOL(1);
-                    }
-                    let title = window_title(hwnd).unwrap_or_default();
-                    collector.push(ProcessInfo { pid, name, title });
-                }
-            }
+impl ProcessInfo {
+    /// A process that need not be running, described only by what rules look at.
+    pub fn described(name: &str, path: Option<&str>, title: Option<&str>) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            path: path.map(str::trim).filter(|path| !path.is_empty()).map(str::to_string),
+            title: title.unwrap_or_default().to_string(),
+            ..Self::default()
         }
     }
 
-    BOOL(1)
+    /// Identifies the entry across enumerations: the executable path, or the name when the
+    /// path is unknown, case-insensitively.
+    pub fn key(&self) -> String {
+        self.path.as_deref().unwrap_or(&self.name).to_lowercase()
+    }
+
+    /// `needle` must already be lowercase.
+    fn contains(&self, needle: &str) -> bool {
+        [
+            Some(self.name.as_str()),
+            self.display_name.as_deref(),
+            Some(self.title.as_str()),
+            self.path.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(needle))
+    }
+}
+
+fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
+struct ProcessDetails {
+    name: String,
+    path: Option<String>,
+    command_line: Option<String>,
+    start_time: Option<u64>,
+    memory_bytes: u64,
+    cpu_usage: f32,
 }
 
-fn window_title(hwnd: HWND) -> Option<String> {
-    let len = unsafe { GetWindowTextLengthW(hwnd) };
-    if len <= 0 {
-        return None;
+impl ProcessDetails {
+    fn from_process(process: &Process) -> Self {
+        Self {
+            name: process.name().to_string(),
+            path: process.exe().map(|exe| exe.display().to_string()),
+            command_line: (!process.cmd().is_empty()).then(|| process.cmd().join(" ")),
+            start_time: Some(process.start_time()).filter(|&time| time > 0),
+            memory_bytes: process.memory(),
+            cpu_usage: process.cpu_usage(),
+        }
     }
-    let mut buffer = vec![0u16; (len + 1) as usize];
-    let copied = unsafe { GetWindowTextW(hwnd, &mut buffer) };
-    if copied <= 0 {
-        return None;
+
+    fn into_info(self, pid: u32, title: String) -> ProcessInfo {
+        let display_name = self.path.as_deref().and_then(display_name_for);
+        ProcessInfo {
+            pid,
+            name: self.name,
+            title,
+            path: self.path,
+            command_line: self.command_line,
+            start_time: self.start_time,
+            memory_bytes: Some(self.memory_bytes),
+            cpu_usage: Some(self.cpu_usage),
+            display_name,
+        }
     }
-    buffer.truncate(copied as usize);
-    Some(String::from_utf16_lossy(&buffer))
 }
 
-fn process_name_for_pid(pid: u32) -> Result<String> {
+fn process_details_for_pid(pid: u32) -> Result<ProcessDetails> {
     let mut sys = PROCESS_SYSTEM.lock();
     let pid_sys = sys_pid_from_u32(pid);
-    if !sys.refresh_process(pid_sys) {
-        sys.refresh_processes();
+    if !sys.refresh_process_specifics(pid_sys, details_refresh_kind()) {
+        sys.refresh_processes_specifics(details_refresh_kind());
     }
     sys.process(pid_sys)
-        .map(|p| p.name().to_string())
+        .map(ProcessDetails::from_process)
         .ok_or_else(|| anyhow!("PID {}의 프로세스를 찾을 수 없습니다", pid))
 }
 
-fn is_banned(name: &str) -> bool {
-    let lower = name.to_ascii_lowercase();
-    BANNED_PROCESSES.iter().any(|b| lower == *b)
+/// Parent chain of `process`, nearest first; see [`walk_ancestors`]. A process keeps its
+/// parent for life, so the chain is cached per PID and start time instead of being walked on
+/// every tick while the same window has focus.
+pub fn ancestors_of(process: &ProcessInfo) -> Vec<ProcessInfo> {
+    let Some(key) = process.start_time.map(|started| (process.pid, started)) else {
+        return walk_ancestors(process.pid);
+    };
+    if let Some(chain) = ANCESTORS.lock().get(&key) {
+        return chain.clone();
+    }
+    let chain = walk_ancestors(process.pid);
+    let mut cache = ANCESTORS.lock();
+    if cache.len() >= ANCESTOR_CACHE_LIMIT {
+        cache.clear();
+    }
+    cache.insert(key, chain.clone());
+    chain
+}
+
+/// Parent chain of `pid`, nearest first, stopping at a missing parent, a cycle, or a parent
+/// that started after its child (a PID reused by an unrelated process). Ancestors need not
+/// own a window, so their titles are empty.
+fn walk_ancestors(pid: u32) -> Vec<ProcessInfo> {
+    let mut sys = PROCESS_SYSTEM.lock();
+    let mut chain = Vec::new();
+    let mut seen = HashSet::from([pid]);
+    let mut current = sys_pid_from_u32(pid);
+    sys.refresh_process_specifics(current, details_refresh_kind());
+
+    while chain.len() < MAX_ANCESTOR_DEPTH {
+        let Some((parent, child_started)) = sys
+            .process(current)
+            .and_then(|child| Some((child.parent()?, child.start_time())))
+        else {
+            break;
+        };
+        if !seen.insert(parent.as_u32())
+            || !sys.refresh_process_specifics(parent, details_refresh_kind())
+        {
+            break;
+        }
+        let Some(process) = sys.process(parent) else {
+            break;
+        };
+        if process.start_time() > child_started {
+            break;
+        }
+        chain.push(ProcessDetails::from_process(process).into_info(parent.as_u32(), String::new()));
+        current = parent;
+    }
+    chain
+}
+
+fn details_refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::new()
+        .with_exe(UpdateKind::OnlyIfNotSet)
+        .with_cmd(UpdateKind::OnlyIfNotSet)
+        .with_memory()
+        .with_cpu()
+}
+
+/// Cached per path: reading the version resource touches the file, and it does not change
+/// while the executable is running.
+fn display_name_for(path: &str) -> Option<String> {
+    let mut cache = DISPLAY_NAMES.lock();
+    if let Some(name) = cache.get(path) {
+        return name.clone();
+    }
+    let name = file_description(path);
+    cache.insert(path.to_string(), name.clone());
+    name
+}
+
+/// Version resources only exist on Windows.
+#[cfg(not(windows))]
+fn file_description(_path: &str) -> Option<String> {
+    None
 }
 
 static PROCESS_SYSTEM: Lazy<Mutex<System>> = Lazy::new(|| Mutex::new(System::new()));
+static DISPLAY_NAMES: Lazy<Mutex<HashMap<String, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+/// Parent chains keyed by PID and start time.
+type AncestorChains = HashMap<(u32, u64), Vec<ProcessInfo>>;
+static ANCESTORS: Lazy<Mutex<AncestorChains>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
 fn sys_pid_from_u32(pid: u32) -> Pid {
     Pid::from(pid as usize)
 }
+
+#[cfg(windows)]
+mod win32 {
+    use std::collections::HashMap;
+
+    use anyhow::{Context, Result};
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows::Win32::Storage::FileSystem::{
+        GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetClassNameW, GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW,
+        GetWindowThreadProcessId, IsWindowVisible,
+    };
+
+    use super::{AppEntry, ProcessInfo, WindowInfo, process_details_for_pid};
+    use crate::pattern::matches_any;
+    use crate::platform::FocusedWindow;
+
+    /// Hidden helper windows every GUI thread owns; listing them would only add noise.
+    const HELPER_WINDOW_CLASSES: &[&str] = &["IME", "MSCTFIME UI"];
+
+    /// Lists applications with a visible, titled window, grouped by executable and skipping
+    /// names that match any `ignored` pattern.
+    pub fn enumerate_gui_processes(ignored: &[String]) -> Result<Vec<AppEntry>> {
+        let mut collector = ProcessCollector {
+            ignored,
+            processes: HashMap::new(),
+            apps: Vec::new(),
+            app_index: HashMap::new(),
+        };
+        unsafe {
+            EnumWindows(
+                Some(enum_windows_proc),
+                LPARAM(&mut collector as *mut _ as isize),
+            )
+            .ok()
+            .context("EnumWindows 호출 실패")?;
+        }
+        Ok(collector.finish())
+    }
+
+    pub fn focused_window() -> Result<Option<FocusedWindow>> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.0.is_null() {
+            return Ok(None);
+        }
+
+        let mut pid = 0u32;
+        unsafe {
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        }
+        if pid == 0 {
+            return Ok(None);
+        }
+
+        let details =
+            process_details_for_pid(pid).context("프로세스 이름을 가져오지 못했습니다")?;
+        let title = window_title(hwnd).unwrap_or_default();
+
+        Ok(Some(FocusedWindow {
+            handle: hwnd.into(),
+            process: details.into_info(pid, title),
+        }))
+    }
+
+    struct ProcessCollector<'a> {
+        ignored: &'a [String],
+        /// Per PID, `None` when ignored or not queryable, so each process is looked up once.
+        processes: HashMap<u32, Option<ProcessInfo>>,
+        apps: Vec<AppEntry>,
+        app_index: HashMap<String, usize>,
+    }
+
+    impl ProcessCollector<'_> {
+        /// Groups by executable, so two different `launcher.exe` binaries stay apart while
+        /// several instances of the same one share an entry.
+        fn push(&mut self, pid: u32, title: String, visible: bool) {
+            let ignored = self.ignored;
+            let is_new_instance = !self.processes.contains_key(&pid);
+            let Some(process) = self
+                .processes
+                .entry(pid)
+                .or_insert_with(|| {
+                    process_details_for_pid(pid)
+                        .ok()
+                        .filter(|details| !matches_any(ignored, &details.name))
+                        .map(|details| details.into_info(pid, String::new()))
+                })
+                .as_ref()
+            else {
+                return;
+            };
+
+            let key = process.key();
+            let index = match self.app_index.get(&key) {
+                Some(&index) => {
+                    if is_new_instance {
+                        self.apps[index].add_instance(process);
+                    }
+                    index
+                }
+                None => {
+                    self.apps.push(AppEntry::new(process.clone()));
+                    self.app_index.insert(key, self.apps.len() - 1);
+                    self.apps.len() - 1
+                }
+            };
+            self.apps[index].push_window(WindowInfo {
+                pid,
+                title,
+                visible,
+            });
+        }
+
+        /// Drops apps that only have hidden windows.
+        fn finish(self) -> Vec<AppEntry> {
+            let mut apps: Vec<AppEntry> = self
+                .apps
+                .into_iter()
+                .filter(|app| app.windows.iter().any(|window| window.visible))
+                .collect();
+            for app in &mut apps {
+                // Stable, so Z order is kept within each half.
+                app.windows.sort_by_key(|window| !window.visible);
+            }
+            apps.sort_by(|a, b| a.process.name.cmp(&b.process.name));
+            apps
+        }
+    }
+
+    unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let collector = unsafe { &mut *(lparam.0 as *mut ProcessCollector) };
+
+        let Some(title) = window_title(hwnd) else {
+            return BOOL(1);
+        };
+        if is_helper_window(hwnd) {
+            return BOOL(1);
+        }
+        let mut pid = 0u32;
+        unsafe {
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        }
+        if pid != 0 {
+            let visible = unsafe { IsWindowVisible(hwnd) }.as_bool();
+            collector.push(pid, title, visible);
+        }
+
+        BOOL(1)
+    }
+
+    fn is_helper_window(hwnd: HWND) -> bool {
+        let mut buffer = [0u16; 64];
+        let len = unsafe { GetClassNameW(hwnd, &mut buffer) };
+        if len <= 0 {
+            return false;
+        }
+        let class = String::from_utf16_lossy(&buffer[..len as usize]);
+        HELPER_WINDOW_CLASSES.contains(&class.as_str())
+    }
+
+    fn window_title(hwnd: HWND) -> Option<String> {
+        let len = unsafe { GetWindowTextLengthW(hwnd) };
+        if len <= 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; (len + 1) as usize];
+        let copied = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+        if copied <= 0 {
+            return None;
+        }
+        buffer.truncate(copied as usize);
+        Some(String::from_utf16_lossy(&buffer))
+    }
+
+    pub(super) fn file_description(path: &str) -> Option<String> {
+        let path = to_wide(path);
+        let size = unsafe { GetFileVersionInfoSizeW(PCWSTR(path.as_ptr()), None) };
+        if size == 0 {
+            return None;
+        }
+        let mut data = vec![0u8; size as usize];
+        unsafe {
+            GetFileVersionInfoW(PCWSTR(path.as_ptr()), 0, size, data.as_mut_ptr().cast())
+        }
+        .ok()?;
+
+        // The first language/code page pair; most executables only carry one.
+        let (translation, len) = query_version_value(&data, "\\VarFileInfo\\Translation")?;
+        if len < 4 {
+            return None;
+        }
+        let (language, code_page) = unsafe {
+            let pair = translation.cast::<u16>();
+            (pair.read_unaligned(), pair.add(1).read_unaligned())
+        };
+
+        let sub_block = format!("\\StringFileInfo\\{language:04x}{code_page:04x}\\FileDescription");
+        let (value, len) = query_version_value(&data, &sub_block)?;
+        // `len` counts UTF-16 units including the terminator.
+        let units = unsafe { std::slice::from_raw_parts(value.cast::<u16>(), len) };
+        let end = units.iter().position(|&unit| unit == 0).unwrap_or(units.len());
+        let description = String::from_utf16_lossy(&units[..end]).trim().to_string();
+        (!description.is_empty()).then_some(description)
+    }
+
+    /// Returns a pointer into `data` and the value length reported by `VerQueryValueW`.
+    fn query_version_value(
+        data: &[u8],
+        sub_block: &str,
+    ) -> Option<(*const std::ffi::c_void, usize)> {
+        let sub_block = to_wide(sub_block);
+        let mut buffer = std::ptr::null_mut();
+        let mut len = 0u32;
+        let found = unsafe {
+            VerQueryValueW(
+                data.as_ptr().cast(),
+                PCWSTR(sub_block.as_ptr()),
+                &mut buffer,
+                &mut len,
+            )
+        };
+        (found.as_bool() && !buffer.is_null() && len > 0)
+            .then_some((buffer.cast_const(), len as usize))
+    }
+
+    fn to_wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::process::AppEntry;
+
+/// Memory and CPU change on nearly every refresh, so they are sent at most this often.
+const METRICS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Payload of the `processes-updated` event.
+///
+/// Every update carries a sequence number. A `Diff` applies on top of the list received with
+/// `base_seq`. Updates not newer than the list a frontend holds are stale and can be dropped;
+/// a diff based on a newer list means one was missed, and the frontend should resync through
+/// `refresh_processes`, whose view model carries the full list and `process_list_seq`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ProcessListUpdate {
+    Full {
+        seq: u64,
+        processes: Vec<AppEntry>,
+    },
+    Diff {
+        seq: u64,
+        base_seq: u64,
+        added: Vec<AppEntry>,
+        changed: Vec<AppEntry>,
+        /// Keys (see [`AppEntry::key`]) of entries that are gone.
+        removed: Vec<String>,
+        /// Keys of the whole list, only when the order changed.
+        order: Option<Vec<String>>,
+        /// New memory and CPU figures of entries that are otherwise unchanged.
+        metrics: Vec<ProcessMetrics>,
+    },
+}
+
+/// The volatile part of an [`AppEntry`], see [`METRICS_INTERVAL`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMetrics {
+    pub key: String,
+    pub memory_bytes: Option<u64>,
+    pub cpu_usage: Option<f32>,
+}
+
+impl ProcessMetrics {
+    fn of(key: &str, app: &AppEntry) -> Self {
+        Self {
+            key: key.to_string(),
+            memory_bytes: app.process.memory_bytes,
+            cpu_usage: app.process.cpu_usage,
+        }
+    }
+}
+
+/// Whether two entries look the same in the picker, ignoring memory and CPU.
+fn same_listing(a: &AppEntry, b: &AppEntry) -> bool {
+    let (process, other) = (&a.process, &b.process);
+    process.pid == other.pid
+        && process.name == other.name
+        && process.title == other.title
+        && process.path == other.path
+        && process.command_line == other.command_line
+        && process.start_time == other.start_time
+        && process.display_name == other.display_name
+        && a.windows == b.windows
+        && a.instance_count == b.instance_count
+}
+
+fn same_metrics(a: &AppEntry, b: &AppEntry) -> bool {
+    a.process.memory_bytes == b.process.memory_bytes && a.process.cpu_usage == b.process.cpu_usage
+}
+
+/// Remembers the last list sent to the frontend so the next one can go out as a diff.
+#[derive(Debug, Default)]
+pub struct ProcessListTracker {
+    seq: u64,
+    /// As the frontend has it: metrics that were held back stay at their old values.
+    last: Vec<AppEntry>,
+    metrics_sent_at: Option<Instant>,
+}
+
+impl ProcessListTracker {
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Sends the whole list regardless of what was sent before.
+    pub fn resync(&mut self, processes: Vec<AppEntry>, at: Instant) -> ProcessListUpdate {
+        self.seq += 1;
+        self.last = processes.clone();
+        self.metrics_sent_at = Some(at);
+        ProcessListUpdate::Full {
+            seq: self.seq,
+            processes,
+        }
+    }
+
+    /// Returns `None` when nothing changed. Falls back to a full list on the first update and
+    /// when a diff would not be smaller than the list itself.
+    ///
+    /// Entries only count as changed when something shown besides memory and CPU differs;
+    /// those two go out as `metrics`, at most once per [`METRICS_INTERVAL`].
+    pub fn update(&mut self, processes: Vec<AppEntry>, at: Instant) -> Option<ProcessListUpdate> {
+        if self.seq == 0 {
+            return Some(self.resync(processes, at));
+        }
+        let metrics_due = self
+            .metrics_sent_at
+            .is_none_or(|sent_at| at.saturating_duration_since(sent_at) >= METRICS_INTERVAL);
+
+        let previous: HashMap<String, &AppEntry> = self
+            .last
+            .iter()
+            .map(|app| (app.key(), app))
+            .collect();
+        let current_keys: Vec<String> = processes.iter().map(AppEntry::key).collect();
+        let current: HashSet<&String> = current_keys.iter().collect();
+
+        let mut added = Vec::new();
+        let mut changed = Vec::new();
+        let mut metrics = Vec::new();
+        let mut next = processes.clone();
+        for ((key, app), sent) in current_keys.iter().zip(&processes).zip(&mut next) {
+            match previous.get(key) {
+                None => added.push(app.clone()),
+                Some(old) if !same_listing(old, app) => changed.push(app.clone()),
+                Some(old) if same_metrics(old, app) => {}
+                Some(_) if metrics_due => metrics.push(ProcessMetrics::of(key, app)),
+                Some(old) => {
+                    sent.process.memory_bytes = old.process.memory_bytes;
+                    sent.process.cpu_usage = old.process.cpu_usage;
+                }
+            }
+        }
+        let removed: Vec<String> = self
+            .last
+            .iter()
+            .map(AppEntry::key)
+            .filter(|key| !current.contains(key))
+            .collect();
+        let previous_order: Vec<String> = self
+            .last
+            .iter()
+            .map(AppEntry::key)
+            .filter(|key| current.contains(key))
+            .collect();
+        let kept_order: Vec<&String> = current_keys
+            .iter()
+            .filter(|key| previous.contains_key(*key))
+            .collect();
+        let reordered = previous_order.iter().ne(kept_order.iter().copied());
+
+        let listing_unchanged = added.is_empty() && changed.is_empty() && removed.is_empty();
+        if listing_unchanged && metrics.is_empty() && !reordered {
+            self.last = next;
+            return None;
+        }
+        if added.len() + changed.len() >= processes.len() {
+            return Some(self.resync(processes, at));
+        }
+
+        let base_seq = self.seq;
+        self.seq += 1;
+        self.last = next;
+        if !metrics.is_empty() {
+            self.metrics_sent_at = Some(at);
+        }
+        // Added entries need the order too, to be placed among the kept ones.
+        let order = (reordered || !added.is_empty()).then_some(current_keys);
+        Some(ProcessListUpdate::Diff {
+            seq: self.seq,
+            base_seq,
+            added,
+            changed,
+            removed,
+            order,
+            metrics,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::ProcessInfo;
+
+    fn app(name: &str, title: &str, cpu_usage: f32) -> AppEntry {
+        AppEntry {
+            process: ProcessInfo {
+                pid: 1,
+                name: name.into(),
+                title: title.into(),
+                cpu_usage: Some(cpu_usage),
+                ..ProcessInfo::default()
+            },
+            windows: Vec::new(),
+            instance_count: 1,
+        }
+    }
+
+    fn list(cpu_usage: f32) -> Vec<AppEntry> {
+        vec![app("a.exe", "A", cpu_usage), app("b.exe", "B", cpu_usage), app("c.exe", "C", cpu_usage)]
+    }
+
+    #[test]
+    fn metrics_alone_are_throttled() {
+        let start = Instant::now();
+        let mut tracker = ProcessListTracker::default();
+        assert!(matches!(tracker.update(list(1.0), start), Some(ProcessListUpdate::Full { .. })));
+
+        assert!(tracker.update(list(2.0), start + Duration::from_secs(1)).is_none());
+
+        let Some(ProcessListUpdate::Diff { changed, metrics, .. }) =
+            tracker.update(list(3.0), start + METRICS_INTERVAL)
+        else {
+            panic!("expected a diff");
+        };
+        assert!(changed.is_empty());
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics[0].cpu_usage, Some(3.0));
+    }
+
+    #[test]
+    fn display_changes_go_out_at_once() {
+        let start = Instant::now();
+        let mut tracker = ProcessListTracker::default();
+        tracker.update(list(1.0), start);
+
+        let mut next = list(2.0);
+        next[1].process.title = "B - renamed".into();
+        let Some(ProcessListUpdate::Diff { seq, base_seq, changed, metrics, .. }) =
+            tracker.update(next, start + Duration::from_secs(1))
+        else {
+            panic!("expected a diff");
+        };
+        assert_eq!((base_seq, seq), (1, 2));
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].process.title, "B - renamed");
+        assert!(metrics.is_empty());
+
+        // The held-back metrics of the other entries are still sent once due.
+        let Some(ProcessListUpdate::Diff { metrics, .. }) =
+            tracker.update(list(2.0), start + METRICS_INTERVAL)
+        else {
+            panic!("expected a diff");
+        };
+        assert_eq!(metrics.iter().map(|m| m.key.as_str()).collect::<Vec<_>>(), ["a.exe", "c.exe"]);
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::rules::{ProcessRule, ResumePolicy};
+use crate::validation::ConfigIssue;
+
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// The part of [`AppConfig`] that differs between profiles. Language and start-up
+/// registration stay global.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileSettings {
+    pub process_rules: Vec<ProcessRule>,
+    pub use_auto_to_en: bool,
+    pub use_mouse_move_event: bool,
+    pub detect_interval_secs: f32,
+    pub max_detect_interval_secs: f32,
+    pub mouse_sensitivity: f32,
+    pub resume_policies: Vec<ResumePolicy>,
+}
+
+impl Default for ProfileSettings {
+    fn default() -> Self {
+        AppConfig::default().profile_settings()
+    }
+}
+
+/// An inactive profile. The active profile lives in the top-level fields of [`AppConfig`]
+/// and is swapped in and out of this list on activation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredProfile {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: ProfileSettings,
+}
+
+impl AppConfig {
+    pub fn profile_settings(&self) -> ProfileSettings {
+        ProfileSettings {
+            process_rules: self.process_rules.clone(),
+            use_auto_to_en: self.use_auto_to_en,
+            use_mouse_move_event: self.use_mouse_move_event,
+            detect_interval_secs: self.detect_interval_secs,
+            max_detect_interval_secs: self.max_detect_interval_secs,
+            mouse_sensitivity: self.mouse_sensitivity,
+            resume_policies: self.resume_policies.clone(),
+        }
+    }
+
+    fn load_profile_settings(&mut self, settings: ProfileSettings) {
+        self.process_rules = settings.process_rules;
+        self.use_auto_to_en = settings.use_auto_to_en;
+        self.use_mouse_move_event = settings.use_mouse_move_event;
+        self.detect_interval_secs = settings.detect_interval_secs;
+        self.max_detect_interval_secs = settings.max_detect_interval_secs;
+        self.mouse_sensitivity = settings.mouse_sensitivity;
+        self.resume_policies = settings.resume_policies;
+    }
+
+    /// All profile names, active one first.
+    pub fn profile_names(&self) -> Vec<String> {
+        std::iter::once(self.active_profile.clone())
+            .chain(self.profiles.iter().map(|profile| profile.name.clone()))
+            .collect()
+    }
+
+    pub fn has_profile(&self, name: &str) -> bool {
+        self.is_active_profile(name)
+            || self
+                .profiles
+                .iter()
+                .any(|profile| same_profile_name(&profile.name, name))
+    }
+
+    fn is_active_profile(&self, name: &str) -> bool {
+        same_profile_name(&self.active_profile, name)
+    }
+
+    fn profile_index(&self, name: &str) -> Option<usize> {
+        self.profiles
+            .iter()
+            .position(|profile| same_profile_name(&profile.name, name))
+    }
+
+    fn validate_new_profile_name(&self, name: &str) -> Result<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("프로필 이름이 비어 있습니다"));
+        }
+        if self.has_profile(name) {
+            return Err(anyhow!("이미 존재하는 프로필 이름입니다: {name}"));
+        }
+        Ok(name.to_string())
+    }
+
+    pub fn create_profile(&mut self, name: &str) -> Result<()> {
+        let name = self.validate_new_profile_name(name)?;
+        self.profiles.push(StoredProfile {
+            name,
+            settings: ProfileSettings::default(),
+        });
+        Ok(())
+    }
+
+    pub fn duplicate_profile(&mut self, source: &str, name: &str) -> Result<()> {
+        let settings = if self.is_active_profile(source) {
+            self.profile_settings()
+        } else {
+            let index = self
+                .profile_index(source)
+                .ok_or_else(|| anyhow!("프로필을 찾을 수 없습니다: {source}"))?;
+            self.profiles[index].settings.clone()
+        };
+        let name = self.validate_new_profile_name(name)?;
+        self.profiles.push(StoredProfile { name, settings });
+        Ok(())
+    }
+
+    pub fn rename_profile(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        let trimmed = new_name.trim();
+        // Changing only the letter case of a name is allowed.
+        let name = if same_profile_name(trimmed, old_name) && !trimmed.is_empty() {
+            trimmed.to_string()
+        } else {
+            self.validate_new_profile_name(new_name)?
+        };
+
+        if self.is_active_profile(old_name) {
+            self.active_profile = name;
+            return Ok(());
+        }
+        let index = self
+            .profile_index(old_name)
+            .ok_or_else(|| anyhow!("프로필을 찾을 수 없습니다: {old_name}"))?;
+        self.profiles[index].name = name;
+        Ok(())
+    }
+
+    pub fn delete_profile(&mut self, name: &str) -> Result<()> {
+        if self.is_active_profile(name) {
+            return Err(anyhow!("활성 프로필은 삭제할 수 없습니다: {name}"));
+        }
+        let index = self
+            .profile_index(name)
+            .ok_or_else(|| anyhow!("프로필을 찾을 수 없습니다: {name}"))?;
+        self.profiles.remove(index);
+        Ok(())
+    }
+
+    /// Stashes the current settings under the active profile and loads `name` in their place.
+    pub fn activate_profile(&mut self, name: &str) -> Result<bool> {
+        if self.is_active_profile(name) {
+            return Ok(false);
+        }
+        let index = self
+            .profile_index(name)
+            .ok_or_else(|| anyhow!("프로필을 찾을 수 없습니다: {name}"))?;
+        let incoming = self.profiles.remove(index);
+        let outgoing = StoredProfile {
+            name: std::mem::replace(&mut self.active_profile, incoming.name),
+            settings: self.profile_settings(),
+        };
+        self.profiles.insert(index, outgoing);
+        self.load_profile_settings(incoming.settings);
+        Ok(true)
+    }
+
+    /// Drops unnamed and duplicate profiles so names stay unique ignoring case, and
+    /// normalizes the settings of every inactive profile like the active one's.
+    pub(crate) fn normalize_profiles(&mut self, issues: &mut Vec<ConfigIssue>) {
+        self.active_profile = self.active_profile.trim().to_string();
+        if self.active_profile.is_empty() {
+            self.active_profile = DEFAULT_PROFILE_NAME.to_string();
+        }
+        let mut seen = vec![self.active_profile.to_lowercase()];
+        self.profiles.retain_mut(|profile| {
+            profile.name = profile.name.trim().to_string();
+            let key = profile.name.to_lowercase();
+            if profile.name.is_empty() || seen.contains(&key) {
+                return false;
+            }
+            seen.push(key);
+            true
+        });
+
+        for (index, profile) in self.profiles.iter_mut().enumerate() {
+            // Stored profiles only hold profile settings, so the rest of the scratch config
+            // stays at its (valid) defaults and reports nothing.
+            let mut scratch = AppConfig::default();
+            scratch.load_profile_settings(std::mem::take(&mut profile.settings));
+            issues.extend(scratch.normalize_with_report().into_iter().map(|mut issue| {
+                issue.field = format!("profiles[{index}].{}", issue.field);
+                issue
+            }));
+            profile.settings = scratch.profile_settings();
+        }
+    }
+}
+
+/// Profile names are compared ignoring case everywhere, matching how uniqueness is enforced.
+fn same_profile_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::IssueKind;
+
+    #[test]
+    fn profile_names_ignore_case() {
+        let mut config = AppConfig::default();
+        config.create_profile("Work").unwrap();
+
+        assert!(config.create_profile("WORK").is_err());
+        assert!(config.activate_profile("work").unwrap());
+        assert_eq!(config.active_profile, "Work");
+        config.rename_profile("default", "Home").unwrap();
+        config.delete_profile("HOME").unwrap();
+        assert_eq!(config.profile_names(), ["Work"]);
+    }
+
+    #[test]
+    fn stored_profiles_are_normalized_and_reported() {
+        let mut config = AppConfig::default();
+        let mut settings = ProfileSettings {
+            detect_interval_secs: 0.0,
+            ..ProfileSettings::default()
+        };
+        settings.process_rules.push(ProcessRule {
+            id: "rule-1".into(),
+            ..ProcessRule::default()
+        });
+        config.profiles.push(StoredProfile { name: "Work".into(), settings });
+
+        let issues = config.normalize_with_report();
+
+        let stored = &config.profiles[0].settings;
+        assert!(stored.detect_interval_secs > 0.0);
+        assert!(stored.process_rules.is_empty());
+        let fields: Vec<_> = issues.iter().map(|issue| (issue.field.as_str(), issue.kind)).collect();
+        assert_eq!(
+            fields,
+            [
+                ("profiles[0].detect_interval_secs", IssueKind::NotPositive),
+                ("profiles[0].process_rules[0].processName", IssueKind::EmptyProcessName),
+            ]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::process::ProcessInfo;
+
+const RECENT_APPS_LIMIT: usize = 50;
+/// Gaps between ticks longer than this (sleep, a stalled loop) are not counted as focus time.
+const MAX_TICK_GAP: Duration = Duration::from_secs(30);
+pub const RECENT_APPS_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// An app that has had the foreground window, persisted in `recent.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentApp {
+    pub name: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// How many times the app gained focus from another app.
+    pub focus_count: u64,
+    pub total_focus_secs: f64,
+    /// Seconds since the Unix epoch.
+    pub last_focused_at: u64,
+}
+
+impl RecentApp {
+    /// See [`ProcessInfo::key`].
+    pub fn key(&self) -> String {
+        self.path.as_deref().unwrap_or(&self.name).to_lowercase()
+    }
+}
+
+/// A [`RecentApp`] as the picker shows it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentAppEntry {
+    #[serde(flatten)]
+    pub app: RecentApp,
+    /// Whether the app was in the last process enumeration.
+    pub running: bool,
+}
+
+/// Most recently focused apps first, bounded to [`RECENT_APPS_LIMIT`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RecentApps {
+    apps: Vec<RecentApp>,
+    #[serde(skip)]
+    current: Option<FocusSpan>,
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    last_saved: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct FocusSpan {
+    key: String,
+    last_tick: Instant,
+}
+
+impl RecentApps {
+    pub fn apps(&self) -> &[RecentApp] {
+        &self.apps
+    }
+
+    /// Called on every monitor tick with the foreground process, or `None` when nothing has
+    /// focus. Time between ticks is added to the app that stayed focused.
+    pub fn record_focus(&mut self, process: Option<&ProcessInfo>, now: Instant) {
+        let Some(process) = process else {
+            self.current = None;
+            return;
+        };
+        let key = process.key();
+
+        match self.current.as_mut() {
+            Some(span) if span.key == key => {
+                let elapsed = now.saturating_duration_since(span.last_tick);
+                span.last_tick = now;
+                if elapsed <= MAX_TICK_GAP {
+                    if let Some(app) = self.apps.iter_mut().find(|app| app.key() == key) {
+                        app.total_focus_secs += elapsed.as_secs_f64();
+                        app.last_focused_at = unix_now();
+                        self.dirty = true;
+                    }
+                }
+            }
+            _ => {
+                self.move_to_front(process, &key);
+                self.current = Some(FocusSpan {
+                    key,
+                    last_tick: now,
+                });
+                self.dirty = true;
+            }
+        }
+    }
+
+    fn move_to_front(&mut self, process: &ProcessInfo, key: &str) {
+        let mut app = match self.apps.iter().position(|app| app.key() == key) {
+            Some(index) => self.apps.remove(index),
+            None => RecentApp {
+                name: process.name.clone(),
+                path: process.path.clone(),
+                display_name: None,
+                focus_count: 0,
+                total_focus_secs: 0.0,
+                last_focused_at: 0,
+            },
+        };
+        app.focus_count += 1;
+        app.last_focused_at = unix_now();
+        if process.display_name.is_some() {
+            app.display_name = process.display_name.clone();
+        }
+        self.apps.insert(0, app);
+        self.apps.truncate(RECENT_APPS_LIMIT);
+    }
+
+    pub fn clear(&mut self) {
+        self.apps.clear();
+        self.current = None;
+        self.dirty = true;
+    }
+
+    /// Whether there are unsaved changes and `interval` has passed since the last save.
+    pub fn save_due(&self, interval: Duration) -> bool {
+        self.dirty && self.last_saved.is_none_or(|at| at.elapsed() >= interval)
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+        self.last_saved = Some(Instant::now());
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(name: &str) -> ProcessInfo {
+        ProcessInfo {
+            name: name.into(),
+            path: Some(format!(r"C:\Apps\{name}")),
+            ..ProcessInfo::default()
+        }
+    }
+
+    fn app<'a>(recent: &'a RecentApps, name: &str) -> &'a RecentApp {
+        recent.apps().iter().find(|app| app.name == name).unwrap()
+    }
+
+    #[test]
+    fn focus_is_counted_only_when_it_comes_from_another_app() {
+        let mut recent = RecentApps::default();
+        let (game, editor) = (process("game.exe"), process("editor.exe"));
+        let start = Instant::now();
+
+        recent.record_focus(Some(&game), start);
+        recent.record_focus(Some(&game), start + Duration::from_secs(1));
+        recent.record_focus(Some(&editor), start + Duration::from_secs(2));
+        recent.record_focus(Some(&game), start + Duration::from_secs(3));
+
+        assert_eq!(app(&recent, "game.exe").focus_count, 2);
+        assert_eq!(app(&recent, "editor.exe").focus_count, 1);
+        assert_eq!(recent.apps()[0].name, "game.exe");
+    }
+
+    #[test]
+    fn time_accumulates_while_the_app_keeps_focus() {
+        let mut recent = RecentApps::default();
+        let game = process("game.exe");
+        let start = Instant::now();
+
+        for secs in 0..=4 {
+            recent.record_focus(Some(&game), start + Duration::from_millis(500 * secs));
+        }
+        assert_eq!(app(&recent, "game.exe").total_focus_secs, 2.0);
+
+        // Losing focus ends the span, so the time away is not counted.
+        recent.record_focus(None, start + Duration::from_secs(3));
+        recent.record_focus(Some(&game), start + Duration::from_secs(10));
+        assert_eq!(app(&recent, "game.exe").total_focus_secs, 2.0);
+        assert_eq!(app(&recent, "game.exe").focus_count, 2);
+    }
+
+    #[test]
+    fn long_gaps_between_ticks_are_not_counted() {
+        let mut recent = RecentApps::default();
+        let game = process("game.exe");
+        let start = Instant::now();
+
+        recent.record_focus(Some(&game), start);
+        recent.record_focus(Some(&game), start + MAX_TICK_GAP + Duration::from_secs(1));
+        assert_eq!(app(&recent, "game.exe").total_focus_secs, 0.0);
+
+        // Counting resumes from the tick after the gap.
+        recent.record_focus(Some(&game), start + MAX_TICK_GAP + Duration::from_secs(3));
+        assert_eq!(app(&recent, "game.exe").total_focus_secs, 2.0);
+    }
+
+    #[test]
+    fn only_the_most_recent_apps_are_kept() {
+        let mut recent = RecentApps::default();
+        let start = Instant::now();
+        for index in 0..=RECENT_APPS_LIMIT {
+            recent.record_focus(Some(&process(&format!("app{index}.exe"))), start);
+        }
+
+        assert_eq!(recent.apps().len(), RECENT_APPS_LIMIT);
+        assert_eq!(recent.apps()[0].name, format!("app{RECENT_APPS_LIMIT}.exe"));
+        assert!(!recent.apps().iter().any(|app| app.name == "app0.exe"));
+    }
+
+    #[test]
+    fn saves_are_throttled_until_the_interval_passes() {
+        let mut recent = RecentApps::default();
+        assert!(!recent.save_due(RECENT_APPS_SAVE_INTERVAL));
+
+        recent.record_focus(Some(&process("game.exe")), Instant::now());
+        assert!(recent.save_due(RECENT_APPS_SAVE_INTERVAL));
+
+        recent.mark_saved();
+        recent.record_focus(Some(&process("editor.exe")), Instant::now());
+        assert!(!recent.save_due(RECENT_APPS_SAVE_INTERVAL));
+
+        recent.last_saved = Instant::now().checked_sub(RECENT_APPS_SAVE_INTERVAL);
+        assert!(recent.save_due(RECENT_APPS_SAVE_INTERVAL));
+    }
+}
@@ -0,0 +1,603 @@
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::pattern::wildcard_match;
+use crate::process::ProcessInfo;
+use crate::rules::{
+    EffectiveSettings, MatchKind, PathMatchKind, ProcessRule, ResumePolicy, RuleAction, TargetInputMode,
+    TitleMatchKind,
+};
+
+/// Process-name matcher built once from a rule's pattern.
+#[derive(Debug, Clone)]
+enum NameMatcher {
+    Exact(String),
+    /// Lowercased pattern.
+    Literal(String),
+    Glob(String),
+    Regex(Regex),
+}
+
+impl NameMatcher {
+    fn compile(kind: MatchKind, pattern: &str) -> Result<Self> {
+        Ok(match kind {
+            MatchKind::Exact => Self::Exact(pattern.to_string()),
+            MatchKind::Literal => Self::Literal(pattern.to_lowercase()),
+            MatchKind::Glob => Self::Glob(pattern.to_string()),
+            MatchKind::Regex => Self::Regex(compile_regex(&format!("^(?:{pattern})$"))?),
+        })
+    }
+
+    fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Exact(pattern) => pattern == name,
+            Self::Literal(pattern) => *pattern == name.to_lowercase(),
+            Self::Glob(pattern) => wildcard_match(pattern, name),
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TitleMatcher {
+    /// Lowercased needle.
+    Substring(String),
+    Regex(Regex),
+}
+
+impl TitleMatcher {
+    fn compile(kind: TitleMatchKind, pattern: &str) -> Result<Self> {
+        Ok(match kind {
+            TitleMatchKind::Substring => Self::Substring(pattern.to_lowercase()),
+            TitleMatchKind::Regex => Self::Regex(compile_regex(pattern)?),
+        })
+    }
+
+    fn is_match(&self, title: &str) -> bool {
+        match self {
+            Self::Substring(needle) => title.to_lowercase().contains(needle.as_str()),
+            Self::Regex(regex) => regex.is_match(title),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PathMatcher {
+    kind: PathMatchKind,
+    /// Normalised with [`normalize_path`].
+    pattern: String,
+}
+
+impl PathMatcher {
+    fn new(kind: PathMatchKind, pattern: &str) -> Self {
+        Self {
+            kind,
+            pattern: normalize_path(pattern),
+        }
+    }
+
+    fn is_match(&self, path: &str) -> bool {
+        let path = normalize_path(path);
+        match self.kind {
+            PathMatchKind::Exact => path == self.pattern,
+            PathMatchKind::Prefix => path
+                .strip_prefix(self.pattern.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('\\')),
+        }
+    }
+}
+
+/// Windows paths are case-insensitive and accept either separator.
+fn normalize_path(path: &str) -> String {
+    path.replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_lowercase()
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .with_context(|| format!("정규식을 해석할 수 없습니다: {pattern}"))
+}
+
+/// Returns `(field, pattern)` for every pattern of `rule` that does not compile.
+pub fn invalid_rule_patterns(rule: &ProcessRule) -> Vec<(&'static str, String)> {
+    let mut invalid = Vec::new();
+    if NameMatcher::compile(rule.match_kind, &rule.process_name).is_err() {
+        invalid.push(("processName", rule.process_name.clone()));
+    }
+    if let Some(pattern) = &rule.title_pattern {
+        if TitleMatcher::compile(rule.title_match_kind, pattern).is_err() {
+            invalid.push(("titlePattern", pattern.clone()));
+        }
+    }
+    invalid
+}
+
+/// Fails on the first pattern of `rule` that does not compile, so an edit can be rejected
+/// instead of saving a rule that never applies.
+pub fn validate_rule_patterns(rule: &ProcessRule) -> Result<()> {
+    CompiledRule::compile(rule).map(|_| ())
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    rule: ProcessRule,
+    /// `None` when the rule leaves the process name open.
+    name: Option<NameMatcher>,
+    path: Option<PathMatcher>,
+    title: Option<TitleMatcher>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &ProcessRule) -> Result<Self> {
+        let name = if rule.process_name.is_empty() {
+            None
+        } else {
+            Some(NameMatcher::compile(rule.match_kind, &rule.process_name)?)
+        };
+        let title = rule
+            .title_pattern
+            .as_deref()
+            .map(|pattern| TitleMatcher::compile(rule.title_match_kind, pattern))
+            .transpose()?;
+        let path = rule
+            .path_pattern
+            .as_deref()
+            .map(|pattern| PathMatcher::new(rule.path_match_kind, pattern));
+        Ok(Self {
+            rule: rule.clone(),
+            name,
+            path,
+            title,
+        })
+    }
+
+    fn matches_name(&self, process_name: &str) -> bool {
+        self.name
+            .as_ref()
+            .is_none_or(|matcher| matcher.is_match(process_name))
+    }
+
+    fn is_match(&self, process: &ProcessInfo) -> bool {
+        self.matches_name(&process.name)
+            && self.path.as_ref().is_none_or(|matcher| {
+                process
+                    .path
+                    .as_deref()
+                    .is_some_and(|path| matcher.is_match(path))
+            })
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|matcher| matcher.is_match(&process.title))
+    }
+
+    /// Lower sorts first: rules with a title condition, then rules with an exact path, then a
+    /// path prefix, then by name match kind.
+    fn precedence(&self) -> (bool, bool, PathMatchKind, MatchKind) {
+        (
+            self.title.is_none(),
+            self.path.is_none(),
+            self.rule.path_match_kind,
+            self.rule.match_kind,
+        )
+    }
+}
+
+/// The rule that matched the focused window and the settings it produces.
+#[derive(Debug, Clone)]
+pub struct Resolution {
+    pub rule: ProcessRule,
+    /// The ancestor process the rule actually matched, when it was inherited.
+    pub via_parent: Option<ProcessInfo>,
+    pub settings: EffectiveSettings,
+}
+
+/// Final outcome of evaluating the rules against a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Decision {
+    Included,
+    Excluded,
+    NoMatch,
+}
+
+/// What became of one matching rule during evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StepOutcome {
+    /// The include rule whose settings are used.
+    Applied,
+    /// An exclude rule that decided the outcome.
+    Excluded,
+    /// An include rule overruled by a matching exclude rule.
+    Blocked,
+    /// An include rule that matched but lost to one with higher precedence.
+    Shadowed,
+}
+
+/// One matching rule in the chain behind a [`Decision`], in the order it was considered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleStep {
+    pub rule: ProcessRule,
+    /// The ancestor process the rule matched, when it was not the focused process itself.
+    pub via_parent: Option<ProcessInfo>,
+    pub outcome: StepOutcome,
+}
+
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub decision: Decision,
+    pub chain: Vec<RuleStep>,
+    /// Set exactly when `decision` is [`Decision::Included`].
+    pub resolution: Option<Resolution>,
+}
+
+/// Outcome of a dry run; see [`RuleSet::test`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestResult {
+    pub decision: Decision,
+    pub matched_rule: Option<ProcessRule>,
+    pub settings: Option<EffectiveSettings>,
+    pub chain: Vec<RuleStep>,
+    /// The input mode the monitor would keep the window in, or `None` when it leaves the
+    /// window alone (no match, excluded, or auto-switching turned off).
+    pub action: Option<TargetInputMode>,
+}
+
+/// The enabled rules of a config with their patterns compiled, ready for per-tick lookups.
+///
+/// When several include rules match the same window, a rule with a title condition wins over
+/// one without; then a rule with an exact path, then one with a path prefix; after that the
+/// most specific name kind wins (exact, then case-insensitive literal, then glob, then regex);
+/// remaining ties go to the rule that comes first in the list.
+///
+/// A matching exclude rule always wins over matching include rules, whatever their precedence,
+/// so "everything under `C:\Games` except `chat_overlay.exe`" needs no extra conditions.
+///
+/// Rules with `include_descendants` are only tried against ancestors when no rule matches the
+/// focused process itself; the nearest ancestor with a matching rule decides, and there too
+/// exclusion wins.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    includes: Vec<CompiledRule>,
+    excludes: Vec<CompiledRule>,
+    use_mouse_move_event: bool,
+    mouse_sensitivity: f32,
+    resume_policies: Vec<ResumePolicy>,
+}
+
+impl RuleSet {
+    /// Rules with a pattern that does not compile are skipped; `normalize_with_report`
+    /// already reports them.
+    pub fn compile(config: &AppConfig) -> Self {
+        let mut rules: Vec<CompiledRule> = config
+            .process_rules
+            .iter()
+            .filter(|rule| rule.enabled && rule.has_condition())
+            .filter_map(|rule| match CompiledRule::compile(rule) {
+                Ok(compiled) => Some(compiled),
+                Err(err) => {
+                    tracing::warn!(?err, rule = %rule.id, "규칙 패턴을 컴파일할 수 없어 건너뜁니다");
+                    None
+                }
+            })
+            .collect();
+        // Stable sort keeps list order within the same precedence.
+        rules.sort_by_key(CompiledRule::precedence);
+        let (includes, excludes) = rules
+            .into_iter()
+            .partition(|compiled| compiled.rule.action == RuleAction::Include);
+
+        Self {
+            includes,
+            excludes,
+            use_mouse_move_event: config.use_mouse_move_event,
+            mouse_sensitivity: config.mouse_sensitivity,
+            resume_policies: config.resume_policies.clone(),
+        }
+    }
+
+    /// Whether the include rule `rule_id` could still apply to this process for at least one
+    /// of its windows.
+    pub fn may_match(&self, rule_id: &str, process_name: &str) -> bool {
+        self.includes.iter().any(|compiled| {
+            compiled.rule.id == rule_id
+                && (compiled.rule.include_descendants || compiled.matches_name(process_name))
+        })
+    }
+
+    /// Whether evaluating this window again still applies the include rule `rule_id`.
+    /// `via_parent` is the ancestor the rule was inherited from, if it was.
+    pub fn still_applies(
+        &self,
+        rule_id: &str,
+        process: &ProcessInfo,
+        via_parent: Option<&ProcessInfo>,
+    ) -> bool {
+        self.evaluate(process, || via_parent.cloned().into_iter().collect())
+            .resolution
+            .is_some_and(|resolution| resolution.rule.id == rule_id)
+    }
+
+    /// Evaluates the focused window. `ancestors` (nearest first) is only called when no rule
+    /// matches the process itself and some rule is inherited, since walking the parent chain
+    /// queries the OS.
+    pub fn evaluate(
+        &self,
+        process: &ProcessInfo,
+        ancestors: impl FnOnce() -> Vec<ProcessInfo>,
+    ) -> Evaluation {
+        if let Some(evaluation) = self.evaluate_one(process, None, |_| true) {
+            return evaluation;
+        }
+        let inherited = |compiled: &CompiledRule| compiled.rule.include_descendants;
+        if self.includes.iter().chain(&self.excludes).any(inherited) {
+            for ancestor in ancestors() {
+                if let Some(evaluation) = self.evaluate_one(&ancestor, Some(&ancestor), inherited) {
+                    return evaluation;
+                }
+            }
+        }
+        Evaluation {
+            decision: Decision::NoMatch,
+            chain: Vec::new(),
+            resolution: None,
+        }
+    }
+
+    /// Evaluates a window that need not exist, the same way the monitor would. There is no
+    /// running process behind it, so rules are not inherited from ancestors.
+    pub fn test(&self, process: &ProcessInfo, use_auto_to_en: bool) -> RuleTestResult {
+        let evaluation = self.evaluate(process, Vec::new);
+        let settings = evaluation.resolution.as_ref().map(|resolution| resolution.settings);
+        RuleTestResult {
+            decision: evaluation.decision,
+            matched_rule: evaluation.resolution.map(|resolution| resolution.rule),
+            settings,
+            chain: evaluation.chain,
+            action: settings
+                .filter(|_| use_auto_to_en)
+                .map(|settings| settings.target_mode),
+        }
+    }
+
+    /// Evaluates the rules passing `eligible` against `process`; `None` when none matches.
+    fn evaluate_one(
+        &self,
+        process: &ProcessInfo,
+        via_parent: Option<&ProcessInfo>,
+        eligible: impl Fn(&CompiledRule) -> bool,
+    ) -> Option<Evaluation> {
+        let matching = |rules: &[CompiledRule]| -> Vec<ProcessRule> {
+            rules
+                .iter()
+                .filter(|compiled| eligible(compiled) && compiled.is_match(process))
+                .map(|compiled| compiled.rule.clone())
+                .collect()
+        };
+        let includes = matching(&self.includes);
+        let excludes = matching(&self.excludes);
+        if includes.is_empty() && excludes.is_empty() {
+            return None;
+        }
+
+        let excluded = !excludes.is_empty();
+        let resolution = (!excluded)
+            .then(|| self.resolution(&includes[0], via_parent.cloned()));
+        let step = |rule: ProcessRule, outcome| RuleStep {
+            rule,
+            via_parent: via_parent.cloned(),
+            outcome,
+        };
+        let mut chain: Vec<RuleStep> = includes
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let outcome = match index {
+                    _ if excluded => StepOutcome::Blocked,
+                    0 => StepOutcome::Applied,
+                    _ => StepOutcome::Shadowed,
+                };
+                step(rule, outcome)
+            })
+            .collect();
+        chain.extend(excludes.into_iter().map(|rule| step(rule, StepOutcome::Excluded)));
+
+        Some(Evaluation {
+            decision: if excluded { Decision::Excluded } else { Decision::Included },
+            chain,
+            resolution,
+        })
+    }
+
+    fn resolution(&self, rule: &ProcessRule, via_parent: Option<ProcessInfo>) -> Resolution {
+        Resolution {
+            rule: rule.clone(),
+            via_parent,
+            settings: EffectiveSettings::new(
+                rule.target_mode,
+                rule.use_mouse_move_event.unwrap_or(self.use_mouse_move_event),
+                rule.mouse_sensitivity.unwrap_or(self.mouse_sensitivity),
+                rule.resume_policies.as_deref().unwrap_or(&self.resume_policies),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_set(rules: Vec<ProcessRule>) -> RuleSet {
+        RuleSet::compile(&AppConfig {
+            process_rules: rules,
+            ..AppConfig::default()
+        })
+    }
+
+    #[test]
+    fn rules_without_a_name_may_match_any_process() {
+        let rules = rule_set(vec![
+            ProcessRule {
+                id: "by-path".into(),
+                path_pattern: Some(r"C:\Games".into()),
+                path_match_kind: PathMatchKind::Prefix,
+                ..ProcessRule::default()
+            },
+            ProcessRule {
+                id: "by-title".into(),
+                title_pattern: Some("Docs".into()),
+                ..ProcessRule::default()
+            },
+            ProcessRule::for_process("by-name", "game.exe"),
+        ]);
+
+        assert!(rules.may_match("by-path", "game.exe"));
+        assert!(rules.may_match("by-title", "browser.exe"));
+        assert!(rules.may_match("by-name", "GAME.EXE"));
+        assert!(!rules.may_match("by-name", "browser.exe"));
+        assert!(!rules.may_match("removed", "game.exe"));
+    }
+
+    fn window(name: &str, path: &str, title: &str) -> ProcessInfo {
+        ProcessInfo {
+            name: name.into(),
+            path: Some(path.into()),
+            title: title.into(),
+            ..ProcessInfo::default()
+        }
+    }
+
+    fn outcomes(evaluation: &Evaluation) -> Vec<(&str, StepOutcome)> {
+        evaluation
+            .chain
+            .iter()
+            .map(|step| (step.rule.id.as_str(), step.outcome))
+            .collect()
+    }
+
+    fn no_ancestors() -> Vec<ProcessInfo> {
+        panic!("ancestors are only needed when nothing matches the process itself")
+    }
+
+    #[test]
+    fn exclude_wins_over_a_more_specific_include() {
+        let rules = rule_set(vec![
+            ProcessRule {
+                id: "docs".into(),
+                process_name: "browser.exe".into(),
+                title_pattern: Some("Docs".into()),
+                ..ProcessRule::default()
+            },
+            ProcessRule {
+                id: "games".into(),
+                path_pattern: Some(r"C:\Apps".into()),
+                path_match_kind: PathMatchKind::Prefix,
+                ..ProcessRule::default()
+            },
+            ProcessRule {
+                id: "no-browser".into(),
+                process_name: "browser.exe".into(),
+                action: RuleAction::Exclude,
+                ..ProcessRule::default()
+            },
+        ]);
+
+        let docs = window("browser.exe", r"C:\Apps\browser.exe", "Docs");
+        let evaluation = rules.evaluate(&docs, no_ancestors);
+        assert_eq!(evaluation.decision, Decision::Excluded);
+        assert!(evaluation.resolution.is_none());
+        assert_eq!(
+            outcomes(&evaluation),
+            [
+                ("docs", StepOutcome::Blocked),
+                ("games", StepOutcome::Blocked),
+                ("no-browser", StepOutcome::Excluded),
+            ]
+        );
+    }
+
+    #[test]
+    fn title_rules_beat_path_rules_which_beat_name_kinds() {
+        let rules = rule_set(vec![
+            ProcessRule {
+                id: "glob".into(),
+                process_name: "game*".into(),
+                match_kind: MatchKind::Glob,
+                ..ProcessRule::default()
+            },
+            ProcessRule::for_process("literal", "GAME.exe"),
+            ProcessRule {
+                id: "prefix".into(),
+                path_pattern: Some(r"C:\Games".into()),
+                path_match_kind: PathMatchKind::Prefix,
+                ..ProcessRule::default()
+            },
+            ProcessRule {
+                id: "exact-path".into(),
+                path_pattern: Some(r"C:\Games\game.exe".into()),
+                ..ProcessRule::default()
+            },
+            ProcessRule {
+                id: "title".into(),
+                title_pattern: Some("Lobby".into()),
+                ..ProcessRule::default()
+            },
+        ]);
+
+        let lobby = rules.evaluate(&window("game.exe", r"C:\Games\game.exe", "Lobby"), no_ancestors);
+        assert_eq!(lobby.decision, Decision::Included);
+        assert_eq!(lobby.resolution.as_ref().unwrap().rule.id, "title");
+        assert_eq!(
+            outcomes(&lobby),
+            [
+                ("title", StepOutcome::Applied),
+                ("exact-path", StepOutcome::Shadowed),
+                ("prefix", StepOutcome::Shadowed),
+                ("literal", StepOutcome::Shadowed),
+                ("glob", StepOutcome::Shadowed),
+            ]
+        );
+
+        let in_game =
+            rules.evaluate(&window("game.exe", r"C:\Games\game.exe", "Match"), no_ancestors);
+        assert_eq!(in_game.resolution.unwrap().rule.id, "exact-path");
+
+        let elsewhere = rules.evaluate(&window("game.exe", r"D:\game.exe", "Match"), no_ancestors);
+        assert_eq!(elsewhere.resolution.unwrap().rule.id, "literal");
+    }
+
+    #[test]
+    fn an_exclude_on_the_child_overrides_an_inherited_include() {
+        let rules = rule_set(vec![
+            ProcessRule {
+                include_descendants: true,
+                ..ProcessRule::for_process("launcher", "launcher.exe")
+            },
+            ProcessRule {
+                action: RuleAction::Exclude,
+                ..ProcessRule::for_process("overlay", "overlay.exe")
+            },
+        ]);
+        let launcher = window("launcher.exe", r"C:\Games\launcher.exe", "Launcher");
+
+        let game = window("game.exe", r"C:\Games\game.exe", "Game");
+        let game = rules.evaluate(&game, || vec![launcher.clone()]);
+        assert_eq!(game.decision, Decision::Included);
+        let resolution = game.resolution.unwrap();
+        assert_eq!(resolution.rule.id, "launcher");
+        assert_eq!(resolution.via_parent.unwrap().name, "launcher.exe");
+
+        let overlay = window("overlay.exe", r"C:\Games\overlay.exe", "Chat");
+        let overlay = rules.evaluate(&overlay, no_ancestors);
+        assert_eq!(overlay.decision, Decision::Excluded);
+        assert_eq!(outcomes(&overlay), [("overlay", StepOutcome::Excluded)]);
+    }
+}
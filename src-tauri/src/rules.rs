@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::ime::ImeStatus;
+use crate::validation::is_positive_finite;
+
+/// Input mode a rule keeps the focused app in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetInputMode {
+    #[default]
+    English,
+    Original,
+}
+
+impl TargetInputMode {
+    pub fn ime_status(self) -> ImeStatus {
+        match self {
+            TargetInputMode::English => ImeStatus::English,
+            TargetInputMode::Original => ImeStatus::Original,
+        }
+    }
+}
+
+/// What a matching rule does. An exclude rule keeps the app out of switching even when an
+/// include rule matches it too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    #[default]
+    Include,
+    Exclude,
+}
+
+/// How a rule's `process_name` is compared with the focused process name.
+/// Declaration order is the precedence order when several rules match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// Case-sensitive equality, as rules have always matched.
+    #[default]
+    Exact,
+    /// Case-insensitive equality. Rules added from the process picker use this.
+    Literal,
+    /// Case-insensitive wildcard pattern with `*` and `?`.
+    Glob,
+    /// Case-insensitive regular expression that must match the whole name.
+    Regex,
+}
+
+/// How a rule's `title_pattern` is compared with the focused window title.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleMatchKind {
+    /// Case-insensitive substring.
+    #[default]
+    Substring,
+    /// Case-insensitive regular expression found anywhere in the title.
+    Regex,
+}
+
+/// How a rule's `path_pattern` is compared with the executable path. Both are case-insensitive
+/// and treat `/` and `\` alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathMatchKind {
+    /// The whole executable path.
+    #[default]
+    Exact,
+    /// A directory (or path) prefix, e.g. `C:\Games` matches `C:\Games\foo\foo.exe`
+    /// but not `C:\GamesOld\foo.exe`.
+    Prefix,
+}
+
+/// How an app paused by hand (a manual override) goes back to automatic switching, besides
+/// `use_mouse_move_event`. Several policies can be combined; whichever fires first resumes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ResumePolicy {
+    /// No keyboard input for `secs` seconds.
+    KeyboardIdle { secs: f32 },
+    /// Another app got focus and then this one again.
+    FocusReturn,
+    /// `secs` seconds after the override started.
+    Timeout { secs: f32 },
+    /// Stays paused until the user switches back by hand; also turns off mouse movement.
+    Never,
+}
+
+impl ResumePolicy {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ResumePolicy::KeyboardIdle { secs } | ResumePolicy::Timeout { secs } => {
+                is_positive_finite(*secs)
+            }
+            ResumePolicy::FocusReturn | ResumePolicy::Never => true,
+        }
+    }
+}
+
+/// Per-app rule. `None` overrides fall back to the global values in [`AppConfig`].
+///
+/// A rule matches on any combination of process name, executable path and window title.
+/// An empty `process_name` means "any process", so such a rule needs another condition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProcessRule {
+    pub id: String,
+    pub process_name: String,
+    pub match_kind: MatchKind,
+    pub title_pattern: Option<String>,
+    pub title_match_kind: TitleMatchKind,
+    pub path_pattern: Option<String>,
+    pub path_match_kind: PathMatchKind,
+    /// Also applies to processes started (directly or indirectly) by a matching process,
+    /// e.g. a game spawned by its launcher.
+    pub include_descendants: bool,
+    pub action: RuleAction,
+    pub enabled: bool,
+    pub target_mode: TargetInputMode,
+    pub use_mouse_move_event: Option<bool>,
+    pub mouse_sensitivity: Option<f32>,
+    /// Replaces the global `resume_policies` for this app.
+    pub resume_policies: Option<Vec<ResumePolicy>>,
+}
+
+impl Default for ProcessRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            process_name: String::new(),
+            match_kind: MatchKind::default(),
+            title_pattern: None,
+            title_match_kind: TitleMatchKind::default(),
+            path_pattern: None,
+            path_match_kind: PathMatchKind::default(),
+            include_descendants: false,
+            action: RuleAction::default(),
+            enabled: true,
+            target_mode: TargetInputMode::default(),
+            use_mouse_move_event: None,
+            mouse_sensitivity: None,
+            resume_policies: None,
+        }
+    }
+}
+
+impl ProcessRule {
+    pub fn for_process(id: impl Into<String>, process_name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            process_name: process_name.into(),
+            match_kind: MatchKind::Literal,
+            ..Self::default()
+        }
+    }
+
+    /// Whether this is the plain name rule the process picker adds (or an older one migrated
+    /// from the selected process list): an include with no title or path condition.
+    pub fn is_picked(&self) -> bool {
+        self.action == RuleAction::Include
+            && matches!(self.match_kind, MatchKind::Exact | MatchKind::Literal)
+            && !self.process_name.is_empty()
+            && self.title_pattern.is_none()
+            && self.path_pattern.is_none()
+    }
+
+    /// [`Self::is_picked`] for `process_name`, compared case-insensitively like the picker's rules.
+    pub fn is_picked_for(&self, process_name: &str) -> bool {
+        self.is_picked() && self.process_name.eq_ignore_ascii_case(process_name)
+    }
+
+    pub fn normalize(&mut self) {
+        self.process_name = self.process_name.trim().to_string();
+        self.title_pattern = self
+            .title_pattern
+            .take()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty());
+        self.path_pattern = self
+            .path_pattern
+            .take()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty());
+        if self.mouse_sensitivity.is_some_and(|value| !is_positive_finite(value)) {
+            self.mouse_sensitivity = None;
+        }
+        if let Some(policies) = &mut self.resume_policies {
+            policies.retain(ResumePolicy::is_valid);
+        }
+    }
+
+    /// A rule without any condition would match every window.
+    pub fn has_condition(&self) -> bool {
+        !self.process_name.is_empty() || self.title_pattern.is_some() || self.path_pattern.is_some()
+    }
+}
+
+/// Settings the monitor applies to the focused app after per-rule overrides.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveSettings {
+    pub target_mode: TargetInputMode,
+    pub use_mouse_move_event: bool,
+    pub mouse_sensitivity: f32,
+    /// Resume a paused app after this many seconds without keyboard input.
+    #[serde(default)]
+    pub resume_after_keyboard_idle_secs: Option<f32>,
+    /// Resume a paused app when it gets focus again after another app had it.
+    #[serde(default)]
+    pub resume_on_focus_return: bool,
+    /// Resume a paused app this many seconds after it was paused.
+    #[serde(default)]
+    pub resume_after_secs: Option<f32>,
+}
+
+impl EffectiveSettings {
+    /// Folds `policies` into the settings. The shortest duration wins when a policy is listed
+    /// twice, and [`ResumePolicy::Never`] switches every policy off, mouse movement included.
+    pub fn new(
+        target_mode: TargetInputMode,
+        use_mouse_move_event: bool,
+        mouse_sensitivity: f32,
+        policies: &[ResumePolicy],
+    ) -> Self {
+        let mut settings = Self {
+            target_mode,
+            use_mouse_move_event,
+            mouse_sensitivity,
+            resume_after_keyboard_idle_secs: None,
+            resume_on_focus_return: false,
+            resume_after_secs: None,
+        };
+        if policies.contains(&ResumePolicy::Never) {
+            settings.use_mouse_move_event = false;
+            return settings;
+        }
+        for policy in policies {
+            match *policy {
+                ResumePolicy::KeyboardIdle { secs } => {
+                    settings.resume_after_keyboard_idle_secs =
+                        shortest(settings.resume_after_keyboard_idle_secs, secs);
+                }
+                ResumePolicy::FocusReturn => settings.resume_on_focus_return = true,
+                ResumePolicy::Timeout { secs } => {
+                    settings.resume_after_secs = shortest(settings.resume_after_secs, secs);
+                }
+                ResumePolicy::Never => {}
+            }
+        }
+        settings
+    }
+}
+
+fn shortest(current: Option<f32>, secs: f32) -> Option<f32> {
+    Some(current.map_or(secs, |current| current.min(secs)))
+}
+
+impl AppConfig {
+    /// Whether the picker already has a plain include rule for this name, in any casing.
+    pub fn has_rule_for(&self, process_name: &str) -> bool {
+        self.process_rules
+            .iter()
+            .any(|rule| rule.is_picked_for(process_name))
+    }
+
+    /// Returns an id of the form `rule-N` that is not used by any existing rule.
+    pub fn next_rule_id(&self) -> String {
+        let next = self
+            .process_rules
+            .iter()
+            .filter_map(|rule| rule.id.strip_prefix("rule-")?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        format!("rule-{next}")
+    }
+}
@@ -1,22 +1,38 @@
-use anyhow::{Context, Result};
+#[cfg(windows)]
+use anyhow::Context;
+use anyhow::Result;
+#[cfg(windows)]
 use windows::core::PCWSTR;
+#[cfg(windows)]
 use windows::Win32::Foundation::ERROR_SUCCESS;
+#[cfg(windows)]
 use windows::Win32::System::Registry::{
     RegCloseKey, RegCreateKeyExW, RegDeleteValueW, RegSetValueExW, HKEY, HKEY_CURRENT_USER,
     KEY_SET_VALUE, REG_OPTION_NON_VOLATILE, REG_SZ,
 };
 
+#[cfg(windows)]
 const RUN_SUBKEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+#[cfg(windows)]
 const VALUE_NAME: &str = "Langcon";
 pub const AUTOSTART_FLAG: &str = "--autostart";
 
 /// Enable or disable auto-start via the Windows Run registry key.
+#[cfg(windows)]
 pub fn set_autostart(enabled: bool) -> Result<()> {
     let exe_path = std::env::current_exe()
         .context("실행 파일 경로를 확인할 수 없습니다")?
         .display()
         .to_string();
-    let command = format!("\"{exe_path}\" {AUTOSTART_FLAG}");
+    let mut command = format!("{} {AUTOSTART_FLAG}", quote_arg(&exe_path));
+    let location = crate::config::determine_config_dir()?;
+    if location.needs_explicit_flag() {
+        command.push_str(&format!(
+            " {} {}",
+            crate::config::CONFIG_DIR_FLAG,
+            quote_arg(&location.dir.display().to_string())
+        ));
+    }
 
     unsafe {
         let mut key = HKEY::default();
@@ -62,10 +78,52 @@ pub fn set_autostart(enabled: bool) -> Result<()> {
     Ok(())
 }
 
+#[cfg(not(windows))]
+pub fn set_autostart(_enabled: bool) -> Result<()> {
+    anyhow::bail!("시작 프로그램 등록은 Windows에서만 지원합니다")
+}
+
+/// Quotes `arg` so `CommandLineToArgvW` reads it back unchanged. Backslashes only escape
+/// before a quote, so the ones ending the argument (`D:\`) are doubled before the closing one.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn quote_arg(arg: &str) -> String {
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for ch in arg.chars() {
+        if ch == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        let escapes = if ch == '"' { backslashes * 2 + 1 } else { backslashes };
+        quoted.extend(std::iter::repeat_n('\\', escapes));
+        quoted.push(ch);
+        backslashes = 0;
+    }
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(windows)]
 fn to_wide(value: &str) -> Vec<u16> {
     value.encode_utf16().chain(std::iter::once(0)).collect()
 }
 
+#[cfg(windows)]
 fn to_wide_with_null(value: &str) -> Vec<u16> {
     to_wide(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_paths_survive_command_line_splitting() {
+        assert_eq!(quote_arg(r"C:\Users\me\Langcon"), r#""C:\Users\me\Langcon""#);
+        assert_eq!(quote_arg(r"D:\"), r#""D:\\""#);
+        assert_eq!(quote_arg(r"D:\cfg\\"), r#""D:\cfg\\\\""#);
+        assert_eq!(quote_arg(r#"a\"b"#), r#""a\\\"b""#);
+        assert_eq!(quote_arg("C:\\Program Files\\Langcon"), r#""C:\Program Files\Langcon""#);
+    }
+}
@@ -1,15 +1,31 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
 
-use crate::config::{AppConfig, AppConfigDto, ConfigManager, sanitize_language};
+use crate::bundle::{BundleScope, ImportMode, ImportPreview, SettingsBundle};
+use crate::config::{
+    AppConfig, AppConfigDto, ConfigManager, MIN_DETECT_INTERVAL_SECS, MIN_MOUSE_SENSITIVITY,
+    MIN_PROCESS_AUTO_REFRESH_SECS, default_ignored_processes, sanitize_language,
+};
+use crate::engine::{Action, Engine, Observation, OverrideKey};
+use crate::history::DraftHistory;
 use crate::ime::ImeStatus;
-use crate::process::ProcessInfo;
+use crate::process::{AppEntry, ProcessInfo, ProcessQuery};
+use crate::pattern::matches_any;
+use crate::process_list::{ProcessListTracker, ProcessListUpdate};
+use crate::recent::{RECENT_APPS_SAVE_INTERVAL, RecentAppEntry, RecentApps};
+use crate::resolver::{Decision, RuleSet, RuleStep, RuleTestResult, validate_rule_patterns};
+use crate::rules::{ProcessRule, ResumePolicy};
+use crate::trace::{TraceEvent, TraceRecorder};
+use crate::validation::{ConfigIssue, IssueKind, merge_issues, upsert_issue};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatusMessage {
     pub key: String,
@@ -18,6 +34,13 @@ pub struct StatusMessage {
 }
 
 impl StatusMessage {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            values: Map::new(),
+        }
+    }
+
     pub fn with_values(
         key: impl Into<String>,
         values: impl IntoIterator<Item = (impl Into<String>, impl Into<serde_json::Value>)>,
@@ -39,17 +62,49 @@ pub struct FocusSnapshot {
     pub process: Option<ProcessInfo>,
     pub ime_status: ImeStatus,
     pub manual_override: bool,
+    /// Seconds left of a time-limited manual override, rounded up; `None` when it has no end.
+    pub manual_override_remaining_secs: Option<u64>,
+    /// The rule that applied to the focused window, if any.
+    pub matched_rule: Option<ProcessRule>,
+    /// Set when `matched_rule` was inherited from this ancestor process.
+    pub matched_via_parent: Option<ProcessInfo>,
+    pub decision: Decision,
+    /// Every rule that matched the window (or the ancestor it inherited from) and its part
+    /// in `decision`.
+    pub rule_chain: Vec<RuleStep>,
     pub updated_at: Option<String>,
 }
 
+/// Outcome of feeding an externally edited `config.json` into the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalConfigChange {
+    Unchanged,
+    Applied,
+    /// The saved config was replaced but the draft has unsaved edits and was kept.
+    Conflict,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppViewModel {
     pub saved_config: AppConfigDto,
     pub draft_config: AppConfigDto,
-    pub available_processes: Vec<ProcessInfo>,
+    /// `available_processes` after `process_query` is applied.
+    pub available_processes: Vec<AppEntry>,
+    pub process_query: ProcessQuery,
+    /// Sequence of the `processes-updated` event `available_processes` corresponds to.
+    pub process_list_seq: u64,
     pub focus: Option<FocusSnapshot>,
+    /// Interval the monitor is polling at right now, between the configured bounds.
+    pub effective_detect_interval_secs: f32,
+    /// File the engine trace is being recorded to, while recording.
+    pub trace_file: Option<String>,
     pub has_unsaved_changes: bool,
+    pub external_config_conflict: bool,
+    pub config_issues: Vec<ConfigIssue>,
+    /// What `undo`/`redo` would revert or reapply, if anything.
+    pub undo_description: Option<StatusMessage>,
+    pub redo_description: Option<StatusMessage>,
     pub status_message: Option<StatusMessage>,
 }
 
@@ -58,44 +113,108 @@ pub struct FocusSnapshotInternal {
     pub process: Option<ProcessInfo>,
     pub ime_status: ImeStatus,
     pub manual_override: bool,
+    pub manual_override_expires_at: Option<Instant>,
+    pub matched_rule: Option<ProcessRule>,
+    pub matched_via_parent: Option<ProcessInfo>,
+    pub decision: Decision,
+    pub rule_chain: Vec<RuleStep>,
     pub updated_at: DateTime<Local>,
 }
 
+impl FocusSnapshotInternal {
+    /// The manual override that applies to this window, if a rule does.
+    pub fn override_key(&self) -> Option<OverrideKey> {
+        let process = self.process.as_ref()?;
+        let rule = self.matched_rule.as_ref()?;
+        Some(OverrideKey::new(&rule.id, &process.name))
+    }
+}
+
+impl From<&FocusSnapshotInternal> for FocusSnapshot {
+    fn from(value: &FocusSnapshotInternal) -> Self {
+        let remaining_secs = value.manual_override_expires_at.map(|expires_at| {
+            let remaining = expires_at.saturating_duration_since(Instant::now());
+            remaining.as_millis().div_ceil(1000) as u64
+        });
+        Self {
+            process: value.process.clone(),
+            ime_status: value.ime_status,
+            manual_override: value.manual_override,
+            manual_override_remaining_secs: remaining_secs,
+            matched_rule: value.matched_rule.clone(),
+            matched_via_parent: value.matched_via_parent.clone(),
+            decision: value.decision,
+            rule_chain: value.rule_chain.clone(),
+            updated_at: Some(value.updated_at.format("%H:%M:%S").to_string()),
+        }
+    }
+}
+
+/// The window a manual override was last seen on, to tell whether its rule still applies
+/// after the rules change.
+#[derive(Debug, Clone)]
+struct PausedWindow {
+    process: ProcessInfo,
+    via_parent: Option<ProcessInfo>,
+}
+
 #[derive(Debug)]
 pub struct AppState {
     config_manager: std::sync::Arc<ConfigManager>,
     saved_config: AppConfig,
+    rules: Arc<RuleSet>,
     draft_config: AppConfig,
-    pub available_processes: Vec<ProcessInfo>,
+    pub available_processes: Vec<AppEntry>,
+    process_query: ProcessQuery,
+    process_list: ProcessListTracker,
+    last_process_refresh: Option<Instant>,
+    effective_detect_interval_secs: f32,
     pub focus: Option<FocusSnapshotInternal>,
-    manual_overrides: HashSet<String>,
+    recent_apps: RecentApps,
+    engine: Engine,
+    paused_windows: HashMap<OverrideKey, PausedWindow>,
+    /// The last focused window a rule applied to; what the tray pauses, since opening the
+    /// tray menu moves focus to the taskbar.
+    last_rule_window: Option<(OverrideKey, PausedWindow)>,
+    trace: Option<TraceRecorder>,
     pub pending_process_refresh: bool,
-    pub last_cursor_pos: Option<(i32, i32)>,
     pub last_status_message: Option<StatusMessage>,
-    last_status_record: Option<StatusRecord>,
     dirty: bool,
-}
-
-#[derive(Debug, Clone)]
-struct StatusRecord {
-    message: StatusMessage,
-    at: DateTime<Local>,
+    external_conflict: bool,
+    config_issues: Vec<ConfigIssue>,
+    history: DraftHistory,
 }
 
 impl AppState {
-    pub fn new(config_manager: std::sync::Arc<ConfigManager>, config: AppConfig) -> Self {
+    pub fn new(
+        config_manager: std::sync::Arc<ConfigManager>,
+        config: AppConfig,
+        config_issues: Vec<ConfigIssue>,
+    ) -> Self {
+        let recent_apps = config_manager.load_recent_apps();
+        let effective_detect_interval_secs = config.detect_interval_secs;
         Self {
             config_manager,
+            rules: Arc::new(RuleSet::compile(&config)),
             saved_config: config.clone(),
             draft_config: config,
             available_processes: Vec::new(),
+            process_query: ProcessQuery::default(),
+            process_list: ProcessListTracker::default(),
+            last_process_refresh: None,
+            effective_detect_interval_secs,
             focus: None,
-            manual_overrides: HashSet::new(),
+            recent_apps,
+            engine: Engine::default(),
+            paused_windows: HashMap::new(),
+            last_rule_window: None,
+            trace: None,
             pending_process_refresh: true,
-            last_cursor_pos: None,
             last_status_message: None,
-            last_status_record: None,
             dirty: false,
+            external_conflict: false,
+            config_issues,
+            history: DraftHistory::default(),
         }
     }
 
@@ -103,6 +222,40 @@ impl AppState {
         &self.saved_config
     }
 
+    /// Compiled rules of the saved config; what the monitor matches the focused app against.
+    /// Shared so callers can resolve (and walk the process tree) without holding the lock.
+    pub fn rules(&self) -> Arc<RuleSet> {
+        self.rules.clone()
+    }
+
+    /// Swaps in a new saved config, recompiling its rules and dropping manual overrides whose
+    /// rule no longer applies to their process.
+    fn replace_saved_config(&mut self, config: AppConfig) {
+        self.rules = Arc::new(RuleSet::compile(&config));
+        self.saved_config = config;
+        let stale: Vec<OverrideKey> = self
+            .engine
+            .manual_overrides()
+            .filter(|key| !self.override_still_applies(key))
+            .cloned()
+            .collect();
+        for key in stale {
+            self.set_manual_override(&key, false, None);
+        }
+    }
+
+    /// Re-evaluates the window the override was last seen on; without one, falls back to
+    /// whether the rule could match the process name at all.
+    fn override_still_applies(&self, key: &OverrideKey) -> bool {
+        match self.paused_windows.get(key) {
+            Some(window) => {
+                self.rules
+                    .still_applies(&key.rule_id, &window.process, window.via_parent.as_ref())
+            }
+            None => self.rules.may_match(&key.rule_id, &key.process_name),
+        }
+    }
+
     pub fn current_language(&self) -> &str {
         &self.draft_config.language
     }
@@ -117,178 +270,870 @@ impl AppState {
         }
 
         let mut cfg = self.draft_config.clone();
-        cfg.normalize();
+        let issues = cfg.normalize_with_report();
         self.config_manager.save(&cfg)?;
-        self.saved_config = cfg.clone();
+        merge_issues(&mut self.config_issues, issues);
+        if cfg.ignored_processes != self.saved_config.ignored_processes {
+            self.pending_process_refresh = true;
+        }
+        self.replace_saved_config(cfg.clone());
         self.draft_config = cfg;
-        self.manual_overrides
-            .retain(|name| self.saved_config.selected_processes.contains(name));
         self.dirty = false;
+        self.external_conflict = false;
         Ok(true)
     }
 
+    /// Resets the draft to the saved config. The discard itself can be undone.
     pub fn discard_changes(&mut self) -> bool {
+        self.external_conflict = false;
+        let saved = self.saved_config.clone();
+        self.edit_draft(StatusMessage::new("toast.history.discarded"), |cfg| *cfg = saved)
+    }
+
+    /// Adopts a config that was changed on disk by another program. The monitor always follows
+    /// the file; the draft is only replaced when it has no unsaved edits.
+    pub fn apply_external_config(
+        &mut self,
+        config: AppConfig,
+        issues: Vec<ConfigIssue>,
+    ) -> ExternalConfigChange {
+        if config == self.saved_config {
+            return ExternalConfigChange::Unchanged;
+        }
+        merge_issues(&mut self.config_issues, issues);
+
+        if config.start_with_windows != self.saved_config.start_with_windows {
+            if let Err(err) = crate::startup::set_autostart(config.start_with_windows) {
+                tracing::warn!(?err, "시작 프로그램 설정 적용에 실패했습니다");
+            }
+        }
+
+        if config.ignored_processes != self.saved_config.ignored_processes {
+            self.pending_process_refresh = true;
+        }
+        self.replace_saved_config(config);
+
         if self.dirty {
+            self.external_conflict = true;
+            ExternalConfigChange::Conflict
+        } else {
+            self.draft_config = self.saved_config.clone();
+            self.history.clear();
+            ExternalConfigChange::Applied
+        }
+    }
+
+    /// Clears a pending conflict, either by dropping the draft in favour of the file on disk
+    /// or by keeping the draft so the next save overwrites the external edit.
+    pub fn resolve_external_conflict(&mut self, accept_external: bool) {
+        if accept_external {
             self.draft_config = self.saved_config.clone();
             self.dirty = false;
-            true
-        } else {
-            false
+            self.history.clear();
         }
+        self.external_conflict = false;
     }
 
     pub fn set_use_auto_to_en(&mut self, enabled: bool) -> Result<()> {
-        if self.draft_config.use_auto_to_en != enabled {
-            self.draft_config.use_auto_to_en = enabled;
-            self.dirty = true;
-        }
+        self.edit_draft(toggle_description("toast.history.autoToEn", enabled), |cfg| {
+            cfg.use_auto_to_en = enabled
+        });
         Ok(())
     }
 
     pub fn set_start_with_windows(&mut self, enabled: bool) -> Result<()> {
         if self.draft_config.start_with_windows != enabled {
             crate::startup::set_autostart(enabled)?;
-            self.draft_config.start_with_windows = enabled;
-            self.dirty = true;
+            self.edit_draft(toggle_description("toast.history.startup", enabled), |cfg| {
+                cfg.start_with_windows = enabled
+            });
         }
         Ok(())
     }
 
     pub fn set_use_mouse_move_event(&mut self, enabled: bool) -> Result<()> {
-        if self.draft_config.use_mouse_move_event != enabled {
-            self.draft_config.use_mouse_move_event = enabled;
-            self.dirty = true;
-        }
+        self.edit_draft(toggle_description("toast.history.mouseMove", enabled), |cfg| {
+            cfg.use_mouse_move_event = enabled
+        });
         Ok(())
     }
 
+    /// Raises the draft's `max_detect_interval_secs` along when it would fall below.
     pub fn set_detect_interval(&mut self, seconds: f32) -> Result<()> {
-        let new_value = seconds.max(0.1);
-        if (self.draft_config.detect_interval_secs - new_value).abs() > f32::EPSILON {
-            self.draft_config.detect_interval_secs = new_value;
-            self.dirty = true;
+        let new_value = seconds.max(MIN_DETECT_INTERVAL_SECS);
+        self.note_clamped("detect_interval_secs", seconds, new_value);
+        let current = self.draft_config.detect_interval_secs;
+        if (current - new_value).abs() > f32::EPSILON {
+            let description = StatusMessage::with_values(
+                "toast.history.detectInterval",
+                [("from", current), ("to", new_value)],
+            );
+            self.edit_draft(description, |cfg| {
+                cfg.detect_interval_secs = new_value;
+                cfg.max_detect_interval_secs = cfg.max_detect_interval_secs.max(new_value);
+            });
+        }
+        Ok(())
+    }
+
+    /// Never below the draft's `detect_interval_secs`.
+    pub fn set_max_detect_interval(&mut self, seconds: f32) -> Result<()> {
+        let new_value = seconds.max(self.draft_config.detect_interval_secs);
+        self.note_clamped("max_detect_interval_secs", seconds, new_value);
+        let current = self.draft_config.max_detect_interval_secs;
+        if (current - new_value).abs() > f32::EPSILON {
+            let description = StatusMessage::with_values(
+                "toast.history.maxDetectInterval",
+                [("from", current), ("to", new_value)],
+            );
+            self.edit_draft(description, |cfg| cfg.max_detect_interval_secs = new_value);
         }
         Ok(())
     }
 
     pub fn set_mouse_sensitivity(&mut self, distance: f32) -> Result<()> {
-        let new_value = distance.max(1.0);
-        if (self.draft_config.mouse_sensitivity - new_value).abs() > f32::EPSILON {
-            self.draft_config.mouse_sensitivity = new_value;
-            self.dirty = true;
+        let new_value = distance.max(MIN_MOUSE_SENSITIVITY);
+        self.note_clamped("mouse_sensitivity", distance, new_value);
+        let current = self.draft_config.mouse_sensitivity;
+        if (current - new_value).abs() > f32::EPSILON {
+            let description = StatusMessage::with_values(
+                "toast.history.mouseSensitivity",
+                [("from", current), ("to", new_value)],
+            );
+            self.edit_draft(description, |cfg| cfg.mouse_sensitivity = new_value);
+        }
+        Ok(())
+    }
+
+    pub fn set_resume_policies(&mut self, policies: Vec<ResumePolicy>) -> Result<()> {
+        if let Some(policy) = policies.iter().find(|policy| !policy.is_valid()) {
+            return Err(anyhow!("재개 조건의 시간은 0보다 커야 합니다: {policy:?}"));
+        }
+        self.edit_draft(StatusMessage::new("toast.history.resumePolicies"), |cfg| {
+            cfg.resume_policies = policies
+        });
+        Ok(())
+    }
+
+    /// `0` (or less) turns auto-refresh off.
+    pub fn set_process_auto_refresh(&mut self, seconds: f32) -> Result<()> {
+        let new_value = if seconds > 0.0 {
+            seconds.max(MIN_PROCESS_AUTO_REFRESH_SECS)
+        } else {
+            0.0
+        };
+        self.note_clamped("process_auto_refresh_secs", seconds, new_value);
+        let current = self.draft_config.process_auto_refresh_secs;
+        if (current - new_value).abs() > f32::EPSILON {
+            let description = StatusMessage::with_values(
+                "toast.history.processAutoRefresh",
+                [("from", current), ("to", new_value)],
+            );
+            self.edit_draft(description, |cfg| cfg.process_auto_refresh_secs = new_value);
         }
         Ok(())
     }
 
+    /// Records (or clears) the issue for a setter that had to clamp its input.
+    fn note_clamped(&mut self, field: &str, requested: f32, applied: f32) {
+        if requested == applied {
+            self.config_issues.retain(|issue| issue.field != field);
+        } else {
+            upsert_issue(
+                &mut self.config_issues,
+                ConfigIssue::new(field, IssueKind::BelowMinimum, requested, applied),
+            );
+        }
+    }
+
     pub fn add_selected_process(&mut self, name: impl Into<String>) -> Result<bool> {
         let process = name.into();
-        if !self.draft_config.selected_processes.contains(&process) {
-            self.draft_config.selected_processes.push(process);
-            self.draft_config.selected_processes.sort();
-            self.draft_config.selected_processes.dedup();
-            self.dirty = true;
+        if !self.draft_config.has_rule_for(&process) {
+            let id = self.draft_config.next_rule_id();
+            let description = named_description("toast.history.ruleAdded", &process);
+            self.edit_draft(description, |cfg| {
+                cfg.process_rules.push(ProcessRule::for_process(id, process))
+            });
             return Ok(true);
         }
         Ok(false)
     }
 
     pub fn remove_selected_process(&mut self, name: &str) -> Result<bool> {
-        let len_before = self.draft_config.selected_processes.len();
-        self.draft_config.selected_processes.retain(|p| p != name);
-        let removed = self.draft_config.selected_processes.len() != len_before;
-        if removed {
-            self.dirty = true;
+        let removed_ids: HashSet<String> = self
+            .draft_config
+            .process_rules
+            .iter()
+            .filter(|rule| rule.is_picked_for(name))
+            .map(|rule| rule.id.clone())
+            .collect();
+        let removed = self.edit_draft(named_description("toast.history.ruleRemoved", name), |cfg| {
+            cfg.process_rules.retain(|rule| !rule.is_picked_for(name))
+        });
+        self.clear_overrides_for(&removed_ids);
+        Ok(removed)
+    }
+
+    /// Drops manual overrides that belonged to rules which no longer exist.
+    fn clear_overrides_for(&mut self, removed_ids: &HashSet<String>) {
+        let stale: Vec<OverrideKey> = self
+            .engine
+            .manual_overrides()
+            .filter(|key| removed_ids.contains(&key.rule_id))
+            .cloned()
+            .collect();
+        for key in stale {
+            self.set_manual_override(&key, false, None);
+        }
+    }
+
+    pub fn add_process_rule(&mut self, mut rule: ProcessRule) -> Result<ProcessRule> {
+        rule.normalize();
+        if !rule.has_condition() {
+            return Err(anyhow!("규칙 조건(프로세스 이름, 경로, 창 제목)이 모두 비어 있습니다"));
+        }
+        validate_rule_patterns(&rule)?;
+        rule.id = self.draft_config.next_rule_id();
+        let added = rule.clone();
+        self.edit_draft(named_description("toast.history.ruleAdded", &rule.process_name), |cfg| {
+            cfg.process_rules.push(added)
+        });
+        Ok(rule)
+    }
+
+    pub fn update_process_rule(&mut self, mut rule: ProcessRule) -> Result<bool> {
+        rule.normalize();
+        if !rule.has_condition() {
+            return Err(anyhow!("규칙 조건(프로세스 이름, 경로, 창 제목)이 모두 비어 있습니다"));
         }
-        self.manual_overrides.remove(name);
+        validate_rule_patterns(&rule)?;
+        let index = self
+            .draft_config
+            .process_rules
+            .iter()
+            .position(|existing| existing.id == rule.id)
+            .ok_or_else(|| anyhow!("규칙을 찾을 수 없습니다: {}", rule.id))?;
+        let description = named_description("toast.history.ruleUpdated", &rule.process_name);
+        Ok(self.edit_draft(description, |cfg| cfg.process_rules[index] = rule))
+    }
+
+    pub fn remove_process_rule(&mut self, id: &str) -> Result<bool> {
+        let Some(name) = self
+            .draft_config
+            .process_rules
+            .iter()
+            .find(|rule| rule.id == id)
+            .map(|rule| rule.process_name.clone())
+        else {
+            return Ok(false);
+        };
+        let removed = self.edit_draft(named_description("toast.history.ruleRemoved", &name), |cfg| {
+            cfg.process_rules.retain(|rule| rule.id != id)
+        });
+        self.clear_overrides_for(&HashSet::from([id.to_string()]));
         Ok(removed)
     }
 
+    pub fn add_ignored_process(&mut self, pattern: &str) -> Result<bool> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(anyhow!("무시할 프로세스 패턴이 비어 있습니다"));
+        }
+        if self
+            .draft_config
+            .ignored_processes
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(pattern))
+        {
+            return Ok(false);
+        }
+        Ok(self.edit_draft(named_description("toast.history.ignoredAdded", pattern), |cfg| {
+            cfg.ignored_processes.push(pattern.to_string())
+        }))
+    }
+
+    pub fn remove_ignored_process(&mut self, pattern: &str) -> Result<bool> {
+        let pattern = pattern.trim();
+        Ok(self.edit_draft(named_description("toast.history.ignoredRemoved", pattern), |cfg| {
+            cfg.ignored_processes
+                .retain(|existing| !existing.eq_ignore_ascii_case(pattern))
+        }))
+    }
+
+    pub fn reset_ignored_processes(&mut self) -> Result<bool> {
+        Ok(self.edit_draft(StatusMessage::new("toast.history.ignoredReset"), |cfg| {
+            cfg.ignored_processes = default_ignored_processes()
+        }))
+    }
+
+    pub fn export_bundle(&self, scope: BundleScope) -> Result<SettingsBundle> {
+        SettingsBundle::from_config(&self.draft_config, scope)
+    }
+
+    pub fn preview_import(&self, bundle: &SettingsBundle, mode: ImportMode) -> Result<ImportPreview> {
+        bundle.preview(&self.draft_config, mode)
+    }
+
+    pub fn apply_import(&mut self, bundle: &SettingsBundle, mode: ImportMode) -> Result<bool> {
+        let next = bundle.apply_to(&self.draft_config, mode)?;
+        Ok(self.edit_draft(StatusMessage::new("toast.history.imported"), |cfg| *cfg = next))
+    }
+
+    pub fn create_profile(&mut self, name: &str) -> Result<()> {
+        self.update_profiles(|cfg| cfg.create_profile(name).map(|_| true))?;
+        Ok(())
+    }
+
+    pub fn duplicate_profile(&mut self, source: &str, name: &str) -> Result<()> {
+        self.update_profiles(|cfg| cfg.duplicate_profile(source, name).map(|_| true))?;
+        Ok(())
+    }
+
+    pub fn rename_profile(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        self.update_profiles(|cfg| cfg.rename_profile(old_name, new_name).map(|_| true))?;
+        Ok(())
+    }
+
+    pub fn delete_profile(&mut self, name: &str) -> Result<()> {
+        self.update_profiles(|cfg| cfg.delete_profile(name).map(|_| true))?;
+        Ok(())
+    }
+
+    /// Switches the active profile. The monitor reads the saved config on every tick, so the
+    /// new profile takes effect on the next one.
+    pub fn activate_profile(&mut self, name: &str) -> Result<bool> {
+        self.update_profiles(|cfg| cfg.activate_profile(name))
+    }
+
+    /// Profile operations are structural, so they are saved immediately and applied to both the
+    /// saved config and the draft. Unsaved edits in the draft travel with their profile.
+    fn update_profiles(&mut self, op: impl Fn(&mut AppConfig) -> Result<bool>) -> Result<bool> {
+        let mut saved = self.saved_config.clone();
+        if !op(&mut saved)? {
+            return Ok(false);
+        }
+        self.config_manager.save(&saved)?;
+
+        let mut draft = self.draft_config.clone();
+        if op(&mut draft).is_err() {
+            draft = saved.clone();
+        }
+        self.replace_saved_config(saved);
+        self.draft_config = draft;
+        self.dirty = self.draft_config != self.saved_config;
+        // Older snapshots belong to a different profile layout and can no longer be restored.
+        self.history.clear();
+        Ok(true)
+    }
+
     pub fn set_language(&mut self, language: impl AsRef<str>) -> Result<bool> {
         let normalized = sanitize_language(language);
-        if self.draft_config.language != normalized {
-            self.draft_config.language = normalized;
-            self.dirty = true;
-            return Ok(true);
+        let description =
+            StatusMessage::with_values("toast.history.language", [("language", normalized.clone())]);
+        Ok(self.edit_draft(description, |cfg| cfg.language = normalized))
+    }
+
+    /// Applies `edit` to the draft and records the previous draft for undo.
+    /// Returns `false` (and records nothing) when the edit changed nothing.
+    fn edit_draft(&mut self, description: StatusMessage, edit: impl FnOnce(&mut AppConfig)) -> bool {
+        let before = self.draft_config.clone();
+        edit(&mut self.draft_config);
+        if self.draft_config == before {
+            return false;
         }
-        Ok(false)
+        self.history.record(description, before);
+        self.dirty = self.draft_config != self.saved_config;
+        true
+    }
+
+    pub fn undo(&mut self) -> Result<Option<StatusMessage>> {
+        let Some(target) = self.history.undo_target() else {
+            return Ok(None);
+        };
+        self.follow_autostart(target.start_with_windows)?;
+        let restored = self.history.undo(&self.draft_config);
+        Ok(restored.map(|(config, description)| {
+            self.restore_draft(config);
+            description
+        }))
+    }
+
+    pub fn redo(&mut self) -> Result<Option<StatusMessage>> {
+        let Some(target) = self.history.redo_target() else {
+            return Ok(None);
+        };
+        self.follow_autostart(target.start_with_windows)?;
+        let restored = self.history.redo(&self.draft_config);
+        Ok(restored.map(|(config, description)| {
+            self.restore_draft(config);
+            description
+        }))
+    }
+
+    /// The autostart entry is written as soon as the toggle changes, so restoring a draft has
+    /// to update it first; if that fails the history is left where it was.
+    fn follow_autostart(&self, start_with_windows: bool) -> Result<()> {
+        if start_with_windows != self.draft_config.start_with_windows {
+            crate::startup::set_autostart(start_with_windows)?;
+        }
+        Ok(())
+    }
+
+    fn restore_draft(&mut self, config: AppConfig) {
+        self.draft_config = config;
+        self.dirty = self.draft_config != self.saved_config;
     }
 
-    pub fn set_available_processes(&mut self, processes: Vec<ProcessInfo>) {
+    /// Returns the update to emit as `processes-updated`, if the visible list changed.
+    pub fn set_available_processes(&mut self, processes: Vec<AppEntry>) -> Option<ProcessListUpdate> {
         self.available_processes = processes;
+        self.last_process_refresh = Some(Instant::now());
+        self.process_list.update(self.visible_processes(), Instant::now())
+    }
+
+    /// A new query can reshuffle the whole list, so it is always sent in full.
+    pub fn set_process_query(&mut self, query: ProcessQuery) -> ProcessListUpdate {
+        self.process_query = query;
+        self.process_list.resync(self.visible_processes(), Instant::now())
+    }
+
+    /// The enumerated processes as the picker shows them.
+    pub fn visible_processes(&self) -> Vec<AppEntry> {
+        self.process_query.apply(&self.available_processes)
+    }
+
+    pub fn set_effective_detect_interval(&mut self, seconds: f32) {
+        self.effective_detect_interval_secs = seconds;
     }
 
     pub fn set_focus(&mut self, focus: Option<FocusSnapshotInternal>) {
+        let noted = focus.as_ref().and_then(|snapshot| {
+            let process = snapshot.process.as_ref()?;
+            Some((process.name.clone(), snapshot.ime_status))
+        });
+        if self.engine.note_focus(noted.clone()) {
+            self.record_trace(TraceEvent::NoteFocus { focus: noted });
+        }
         self.focus = focus;
+        if let Some(entry) = self.focused_rule_window() {
+            self.last_rule_window = Some(entry);
+        }
+        self.remember_paused_window();
     }
 
-    pub fn set_manual_override(&mut self, process_name: &str, enabled: bool) {
-        if enabled {
-            self.manual_overrides.insert(process_name.to_string());
-        } else {
-            self.manual_overrides.remove(process_name);
+    /// The focused window and the override key of its rule, if a rule applies to it.
+    fn focused_rule_window(&self) -> Option<(OverrideKey, PausedWindow)> {
+        let focus = self.focus.as_ref()?;
+        let window = PausedWindow {
+            process: focus.process.clone()?,
+            via_parent: focus.matched_via_parent.clone(),
+        };
+        Some((focus.override_key()?, window))
+    }
+
+    /// Keeps the focused window of a paused app, with its latest title, for
+    /// [`Self::override_still_applies`].
+    fn remember_paused_window(&mut self) {
+        let Some((key, window)) = self.focused_rule_window() else {
+            return;
+        };
+        if self.engine.manual_override_for(&key) {
+            self.paused_windows.insert(key, window);
+        }
+    }
+
+    /// Pauses the app last focused under a rule for `duration`, as long as that rule still
+    /// applies to it. Returns the name of the paused process.
+    pub fn pause_last_focused(&mut self, duration: Duration) -> Option<String> {
+        let (key, window) = self.last_rule_window.clone()?;
+        if !self
+            .rules
+            .still_applies(&key.rule_id, &window.process, window.via_parent.as_ref())
+        {
+            return None;
+        }
+        self.set_manual_override(&key, true, Some(duration));
+        self.paused_windows.insert(key.clone(), window);
+        Some(key.process_name)
+    }
+
+    /// Runs the switching engine on one monitor tick.
+    pub fn observe(&mut self, observation: &Observation) -> Vec<Action> {
+        let actions = self.engine.observe(observation);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.observe(observation, &actions);
+        }
+        actions
+    }
+
+    /// See [`Engine::switched`].
+    pub fn switched(&mut self, toggled: bool, at: Instant) -> Option<StatusMessage> {
+        let status = self.engine.switched(toggled, at);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.switched(toggled, at, status.as_ref());
+        }
+        status
+    }
+
+    /// Starts writing every engine input and decision to a new JSON Lines trace under the
+    /// config directory, see [`ConfigManager::new_trace_path`]. Replaces a recording already
+    /// in progress.
+    pub fn start_trace(&mut self, file_name: Option<&str>) -> Result<PathBuf> {
+        let path = self.config_manager.new_trace_path(file_name)?;
+        let recorder = TraceRecorder::create(&path, &self.engine)?;
+        self.trace = Some(recorder);
+        Ok(path)
+    }
+
+    /// Returns the file of the recording that was stopped, if any.
+    pub fn stop_trace(&mut self) -> Option<PathBuf> {
+        self.trace.take().map(|trace| trace.path().to_path_buf())
+    }
+
+    fn record_trace(&mut self, event: TraceEvent) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.record(&event);
+        }
+    }
+
+    /// Dry run of the draft rules, so edits can be checked before saving.
+    pub fn test_rule(&self, process_name: &str, path: Option<&str>, title: Option<&str>) -> RuleTestResult {
+        let process = ProcessInfo::described(process_name, path, title);
+        RuleSet::compile(&self.draft_config).test(&process, self.draft_config.use_auto_to_en)
+    }
+
+    pub fn record_focus(&mut self, process: Option<&ProcessInfo>) {
+        self.recent_apps.record_focus(process, Instant::now());
+    }
+
+    /// Writes `recent.json` at most once per [`RECENT_APPS_SAVE_INTERVAL`], or right away
+    /// with `force` (e.g. on shutdown).
+    pub fn save_recent_apps(&mut self, force: bool) {
+        let interval = if force { Duration::ZERO } else { RECENT_APPS_SAVE_INTERVAL };
+        if !self.recent_apps.save_due(interval) {
+            return;
+        }
+        match self.config_manager.save_recent_apps(&self.recent_apps) {
+            Ok(()) => self.recent_apps.mark_saved(),
+            Err(err) => tracing::warn!(?err, "최근 사용 앱 저장 실패"),
+        }
+    }
+
+    /// Recently focused apps, most recent first, leaving out ignored processes.
+    pub fn recent_apps(&self) -> Vec<RecentAppEntry> {
+        let ignored = &self.active_config().ignored_processes;
+        let running: HashSet<String> = self.available_processes.iter().map(AppEntry::key).collect();
+        self.recent_apps
+            .apps()
+            .iter()
+            .filter(|app| !matches_any(ignored, &app.name))
+            .map(|app| RecentAppEntry {
+                running: running.contains(&app.key()),
+                app: app.clone(),
+            })
+            .collect()
+    }
+
+    pub fn clear_recent_apps(&mut self) -> Result<()> {
+        self.recent_apps.clear();
+        self.config_manager.save_recent_apps(&self.recent_apps)?;
+        self.recent_apps.mark_saved();
+        Ok(())
+    }
+
+    /// With a `duration` the override expires on its own, see [`Engine::set_manual_override`].
+    pub fn set_manual_override(&mut self, key: &OverrideKey, enabled: bool, duration: Option<Duration>) {
+        let at = Instant::now();
+        self.engine.set_manual_override(key, enabled, at, duration);
+        if let Some(trace) = self.trace.as_mut() {
+            trace.manual_override(key, enabled, at, duration);
         }
+        let expires_at = self.engine.manual_override_expiry(key);
         if let Some(snapshot) = self.focus.as_mut() {
-            if let Some(proc) = &snapshot.process {
-                if proc.name == process_name {
-                    snapshot.manual_override = enabled;
-                }
+            if snapshot.override_key().as_ref() == Some(key) {
+                snapshot.manual_override = enabled;
+                snapshot.manual_override_expires_at = expires_at;
             }
         }
+        if enabled {
+            self.remember_paused_window();
+        } else {
+            self.paused_windows.remove(key);
+        }
     }
 
-    pub fn manual_override_for(&self, process_name: &str) -> bool {
-        self.manual_overrides.contains(process_name)
+    pub fn manual_override_for(&self, key: &OverrideKey) -> bool {
+        self.engine.manual_override_for(key)
+    }
+
+    pub fn manual_override_expiry(&self, key: &OverrideKey) -> Option<Instant> {
+        self.engine.manual_override_expiry(key)
     }
 
     pub fn request_process_refresh(&mut self) {
         self.pending_process_refresh = true;
     }
 
+    /// True when a refresh was requested or the auto-refresh interval has elapsed.
     pub fn take_process_refresh_request(&mut self) -> bool {
         if self.pending_process_refresh {
             self.pending_process_refresh = false;
-            true
-        } else {
-            false
+            return true;
         }
+        let interval = self.active_config().process_auto_refresh_secs;
+        interval > 0.0
+            && self
+                .last_process_refresh
+                .is_none_or(|at| at.elapsed() >= Duration::from_secs_f32(interval))
     }
 
     pub fn update_status_message(&mut self, message: Option<StatusMessage>) {
         self.last_status_message = message;
     }
 
-    pub fn should_emit_status(&self, message: &StatusMessage, cooldown_ms: i64) -> bool {
-        if let Some(record) = &self.last_status_record {
-            if record.message.key == message.key && record.message.values == message.values {
-                let elapsed = (Local::now() - record.at).num_milliseconds();
-                return elapsed >= cooldown_ms;
-            }
-        }
-        true
-    }
-
-    pub fn record_status(&mut self, message: StatusMessage) {
-        self.last_status_record = Some(StatusRecord {
-            message,
-            at: Local::now(),
-        });
-    }
-
     pub fn to_view_model(&mut self) -> AppViewModel {
         AppViewModel {
             saved_config: AppConfigDto::from(&self.saved_config),
             draft_config: AppConfigDto::from(&self.draft_config),
-            available_processes: self.available_processes.clone(),
-            focus: self.focus.as_ref().map(|f| FocusSnapshot {
-                process: f.process.clone(),
-                ime_status: f.ime_status,
-                manual_override: f.manual_override,
-                updated_at: Some(f.updated_at.format("%H:%M:%S").to_string()),
-            }),
+            available_processes: self.visible_processes(),
+            process_query: self.process_query.clone(),
+            process_list_seq: self.process_list.seq(),
+            focus: self.focus.as_ref().map(FocusSnapshot::from),
+            effective_detect_interval_secs: self.effective_detect_interval_secs,
+            trace_file: self
+                .trace
+                .as_ref()
+                .map(|trace| trace.path().display().to_string()),
             has_unsaved_changes: self.has_unsaved_changes(),
+            external_config_conflict: self.external_conflict,
+            config_issues: self.config_issues.clone(),
+            undo_description: self.history.undo_description().cloned(),
+            redo_description: self.history.redo_description().cloned(),
             status_message: None,
         }
     }
 }
 
+fn toggle_description(key: &str, enabled: bool) -> StatusMessage {
+    let suffix = if enabled { "enabled" } else { "disabled" };
+    StatusMessage::new(format!("{key}.{suffix}"))
+}
+
+fn named_description(key: &str, name: &str) -> StatusMessage {
+    StatusMessage::with_values(key, [("name", name.to_string())])
+}
+
 pub type SharedAppState = std::sync::Arc<parking_lot::Mutex<AppState>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigDirSource, ConfigLocation};
+    use crate::rules::{MatchKind, RuleAction, TargetInputMode, TitleMatchKind};
+
+    fn test_state() -> (tempfile::TempDir, AppState) {
+        let dir = tempfile::tempdir().unwrap();
+        let location = ConfigLocation {
+            dir: dir.path().to_path_buf(),
+            source: ConfigDirSource::CliFlag,
+        };
+        let (manager, config, issues) = ConfigManager::load_or_create_in(location).unwrap();
+        (dir, AppState::new(Arc::new(manager), config, issues))
+    }
+
+    #[test]
+    fn ignored_processes_are_removed_ignoring_case() {
+        let (_dir, mut state) = test_state();
+        assert!(state.add_ignored_process("Game.exe").unwrap());
+        assert!(!state.add_ignored_process("GAME.EXE").unwrap());
+
+        assert!(state.remove_ignored_process(" game.exe ").unwrap());
+        assert!(!state.draft_config.ignored_processes.iter().any(|p| p.eq_ignore_ascii_case("game.exe")));
+    }
+
+    #[test]
+    fn picked_processes_match_ignoring_case() {
+        let (_dir, mut state) = test_state();
+        state.add_selected_process("Game.exe").unwrap();
+        assert_eq!(state.draft_config.process_rules[0].match_kind, MatchKind::Literal);
+    }
+
+    #[test]
+    fn picking_another_casing_does_not_add_a_duplicate() {
+        let (_dir, mut state) = test_state();
+        assert!(state.add_selected_process("javaw.exe").unwrap());
+        assert!(!state.add_selected_process("JavaW.exe").unwrap());
+        assert_eq!(state.draft_config.process_rules.len(), 1);
+
+        assert!(state.remove_selected_process("JAVAW.EXE").unwrap());
+        assert!(state.draft_config.process_rules.is_empty());
+    }
+
+    #[test]
+    fn unpicking_keeps_exclude_and_scoped_rules() {
+        let (_dir, mut state) = test_state();
+        state.add_selected_process("browser.exe").unwrap();
+        let scoped = state
+            .add_process_rule(ProcessRule {
+                process_name: "browser.exe".into(),
+                title_pattern: Some("Docs".into()),
+                ..ProcessRule::default()
+            })
+            .unwrap();
+        let exclude = state
+            .add_process_rule(ProcessRule {
+                process_name: "browser.exe".into(),
+                action: RuleAction::Exclude,
+                ..ProcessRule::default()
+            })
+            .unwrap();
+
+        assert!(state.remove_selected_process("browser.exe").unwrap());
+        let remaining: Vec<&str> = state.draft_config.process_rules.iter().map(|rule| rule.id.as_str()).collect();
+        assert_eq!(remaining, [scoped.id.as_str(), exclude.id.as_str()]);
+    }
+
+    #[test]
+    fn removing_a_rule_ends_its_overrides() {
+        let (_dir, mut state) = test_state();
+        state.add_selected_process("game.exe").unwrap();
+        state.add_selected_process("editor.exe").unwrap();
+        let game = OverrideKey::new(state.draft_config.process_rules[0].id.clone(), "game.exe");
+        let editor = OverrideKey::new(state.draft_config.process_rules[1].id.clone(), "editor.exe");
+        state.set_manual_override(&game, true, None);
+        state.set_manual_override(&editor, true, None);
+
+        let id = game.rule_id.clone();
+        assert!(state.remove_process_rule(&id).unwrap());
+        assert!(!state.manual_override_for(&game));
+        assert!(state.manual_override_for(&editor));
+
+        assert!(state.remove_selected_process("editor.exe").unwrap());
+        assert!(!state.manual_override_for(&editor));
+    }
+
+    #[test]
+    fn rules_with_invalid_patterns_are_rejected() {
+        let (_dir, mut state) = test_state();
+        let rule = ProcessRule {
+            process_name: "game(".into(),
+            match_kind: MatchKind::Regex,
+            ..ProcessRule::default()
+        };
+        assert!(state.add_process_rule(rule.clone()).is_err());
+
+        let added = state
+            .add_process_rule(ProcessRule { process_name: "game.exe".into(), ..rule })
+            .unwrap();
+        let broken = ProcessRule {
+            title_pattern: Some("[".into()),
+            title_match_kind: TitleMatchKind::Regex,
+            ..added
+        };
+        assert!(state.update_process_rule(broken).is_err());
+        assert_eq!(state.draft_config.process_rules[0].title_pattern, None);
+    }
+
+    #[test]
+    fn overrides_end_when_their_rule_stops_applying() {
+        let (_dir, mut state) = test_state();
+        let rule = state
+            .add_process_rule(ProcessRule {
+                process_name: "browser.exe".into(),
+                title_pattern: Some("Docs".into()),
+                ..ProcessRule::default()
+            })
+            .unwrap();
+        state.save_changes().unwrap();
+        let browser = ProcessInfo {
+            pid: 1,
+            name: "browser.exe".into(),
+            title: "Docs - Browser".into(),
+            ..ProcessInfo::default()
+        };
+        state.set_focus(Some(FocusSnapshotInternal {
+            process: Some(browser),
+            ime_status: ImeStatus::Original,
+            manual_override: false,
+            manual_override_expires_at: None,
+            matched_rule: Some(rule.clone()),
+            matched_via_parent: None,
+            decision: Decision::Included,
+            rule_chain: Vec::new(),
+            updated_at: Local::now(),
+        }));
+        let key = OverrideKey::new(&rule.id, "browser.exe");
+        state.set_manual_override(&key, true, None);
+
+        let renamed = ProcessRule {
+            target_mode: TargetInputMode::Original,
+            ..rule.clone()
+        };
+        state.update_process_rule(renamed.clone()).unwrap();
+        state.save_changes().unwrap();
+        assert!(state.manual_override_for(&key));
+
+        // Same process name, but the paused window's title no longer matches.
+        let retitled = ProcessRule {
+            title_pattern: Some("Mail".into()),
+            ..renamed
+        };
+        state.update_process_rule(retitled).unwrap();
+        state.save_changes().unwrap();
+        assert!(!state.manual_override_for(&key));
+    }
+
+    #[test]
+    fn tray_pauses_the_app_focused_before_the_taskbar() {
+        let (_dir, mut state) = test_state();
+        let rule = state
+            .add_process_rule(ProcessRule {
+                process_name: "notepad.exe".into(),
+                ..ProcessRule::default()
+            })
+            .unwrap();
+        state.save_changes().unwrap();
+        let focus = |name: &str, rule: Option<ProcessRule>| FocusSnapshotInternal {
+            process: Some(ProcessInfo {
+                pid: 1,
+                name: name.into(),
+                ..ProcessInfo::default()
+            }),
+            ime_status: ImeStatus::Original,
+            manual_override: false,
+            manual_override_expires_at: None,
+            matched_rule: rule,
+            matched_via_parent: None,
+            decision: Decision::Included,
+            rule_chain: Vec::new(),
+            updated_at: Local::now(),
+        };
+        assert!(state.pause_last_focused(Duration::from_secs(60)).is_none());
+
+        state.set_focus(Some(focus("notepad.exe", Some(rule.clone()))));
+        state.set_focus(Some(focus("explorer.exe", None)));
+        let paused = state.pause_last_focused(Duration::from_secs(60));
+
+        assert_eq!(paused.as_deref(), Some("notepad.exe"));
+        let key = OverrideKey::new(&rule.id, "notepad.exe");
+        assert!(state.manual_override_for(&key));
+        assert!(state.manual_override_expiry(&key).is_some());
+    }
+
+    // Relies on autostart failing; on Windows undo would edit the real Run key.
+    #[cfg(not(windows))]
+    #[test]
+    fn failed_autostart_keeps_undo_history_in_place() {
+        let (_dir, mut state) = test_state();
+        // Registering autostart always fails off Windows, so undoing this edit cannot apply it.
+        let enabled = !state.draft_config.start_with_windows;
+        state.edit_draft(StatusMessage::new("toast.history.autostart"), |cfg| {
+            cfg.start_with_windows = enabled
+        });
+
+        assert!(state.undo().is_err());
+        assert_eq!(state.draft_config.start_with_windows, enabled);
+        assert!(state.history.undo_description().is_some());
+        assert!(state.history.redo_description().is_none());
+    }
+}
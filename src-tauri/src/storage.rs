@@ -0,0 +1,237 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, anyhow};
+
+/// Writes `contents` to a sibling temp file, flushes it to disk and renames it over `path`,
+/// so a crash mid-write never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("잘못된 파일 경로입니다: {}", path.display()))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let result = (|| -> Result<()> {
+        let mut file = File::create(&tmp_path).context("임시 파일 생성 실패")?;
+        file.write_all(contents).context("임시 파일 쓰기 실패")?;
+        file.sync_all().context("임시 파일 동기화 실패")?;
+        drop(file);
+        fs::rename(&tmp_path, path).context("임시 파일 교체 실패")?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+const READ_ATTEMPTS: u32 = 3;
+const READ_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Reads a file, retrying a few times when another process (an antivirus scanner, an editor
+/// mid-save) holds it. A missing file is reported right away.
+pub fn read_to_string_retrying(path: &Path) -> std::io::Result<String> {
+    let mut attempt = 1;
+    loop {
+        match fs::read_to_string(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound && attempt < READ_ATTEMPTS => {
+                tracing::debug!(?err, attempt, path = %path.display(), "파일 읽기 재시도");
+                attempt += 1;
+                thread::sleep(READ_RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Moves an unreadable file aside as `<name>.corrupt-<timestamp>` so it is kept for inspection
+/// but no longer loaded.
+pub fn quarantine(path: &Path) -> Result<PathBuf> {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let target = path.with_file_name(format!("{stem}.corrupt-{}.json", timestamp()));
+    fs::rename(path, &target).context("손상된 파일 격리 실패")?;
+    Ok(target)
+}
+
+/// Keeps the last `keep` good copies of a file as `<prefix>.bak.<timestamp>.json` in `dir`.
+#[derive(Debug, Clone)]
+pub struct RollingBackups {
+    dir: PathBuf,
+    prefix: &'static str,
+    keep: usize,
+    min_interval: Option<Duration>,
+}
+
+impl RollingBackups {
+    pub fn new(dir: PathBuf, prefix: &'static str, keep: usize) -> Self {
+        Self {
+            dir,
+            prefix,
+            keep,
+            min_interval: None,
+        }
+    }
+
+    /// Skips snapshots while the newest backup is younger than `interval`.
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = Some(interval);
+        self
+    }
+
+    /// Copies `source` into the backup set if it exists and `is_valid` accepts its contents.
+    pub fn snapshot(&self, source: &Path, is_valid: impl Fn(&str) -> bool) -> Result<Option<PathBuf>> {
+        if !source.exists() {
+            return Ok(None);
+        }
+        if let (Some(interval), Some(newest)) = (self.min_interval, self.list()?.first()) {
+            let recent = fs::metadata(newest)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .is_some_and(|age| age < interval);
+            if recent {
+                return Ok(None);
+            }
+        }
+
+        let raw = fs::read_to_string(source).context("백업할 파일을 읽을 수 없습니다")?;
+        if !is_valid(&raw) {
+            return Ok(None);
+        }
+
+        fs::create_dir_all(&self.dir).context("백업 디렉터리 생성 실패")?;
+        let target = self
+            .dir
+            .join(format!("{}.bak.{}.json", self.prefix, timestamp()));
+        write_atomic(&target, raw.as_bytes())?;
+        self.prune()?;
+        Ok(Some(target))
+    }
+
+    /// Backups in this set, newest first.
+    pub fn list(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let marker = format!("{}.bak.", self.prefix);
+        let mut entries: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .context("백업 디렉터리를 읽을 수 없습니다")?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&marker) && name.ends_with(".json"))
+            })
+            .collect();
+        // Timestamps are zero-padded, so lexical order is chronological.
+        entries.sort();
+        entries.reverse();
+        Ok(entries)
+    }
+
+    fn prune(&self) -> Result<()> {
+        for stale in self.list()?.into_iter().skip(self.keep) {
+            if let Err(err) = fs::remove_file(&stale) {
+                tracing::warn!(?err, path = %stale.display(), "오래된 백업 삭제 실패");
+            }
+        }
+        Ok(())
+    }
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_atomic_replaces_the_file_without_leftovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["config.json"]);
+    }
+
+    #[test]
+    fn write_atomic_failure_keeps_the_original() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory cannot be renamed over by a file.
+        let path = dir.path().join("config.json");
+        fs::create_dir(&path).unwrap();
+
+        assert!(write_atomic(&path, b"new").is_err());
+        assert!(path.is_dir());
+        assert!(!dir.path().join("config.json.tmp").exists());
+    }
+
+    #[test]
+    fn quarantine_moves_the_file_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        let moved = quarantine(&path).unwrap();
+
+        assert!(!path.exists());
+        let name = moved.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("config.corrupt-") && name.ends_with(".json"), "{name}");
+        assert_eq!(fs::read_to_string(moved).unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn backups_keep_only_the_newest_valid_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("config.json");
+        let backups = RollingBackups::new(dir.path().join("backups"), "config", 2);
+
+        for round in 0..4 {
+            fs::write(&source, format!("valid {round}")).unwrap();
+            assert!(backups.snapshot(&source, |raw| raw.starts_with("valid")).unwrap().is_some());
+            // Timestamps have millisecond resolution.
+            thread::sleep(Duration::from_millis(5));
+        }
+        fs::write(&source, "broken").unwrap();
+        assert!(backups.snapshot(&source, |raw| raw.starts_with("valid")).unwrap().is_none());
+
+        let kept: Vec<String> = backups
+            .list()
+            .unwrap()
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect();
+        assert_eq!(kept, ["valid 3", "valid 2"]);
+    }
+
+    #[test]
+    fn backups_wait_for_the_minimum_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("window.json");
+        fs::write(&source, "{}").unwrap();
+        let backups = RollingBackups::new(dir.path().join("backups"), "window", 5)
+            .with_min_interval(Duration::from_secs(3600));
+
+        assert!(backups.snapshot(&source, |_| true).unwrap().is_some());
+        assert!(backups.snapshot(&source, |_| true).unwrap().is_none());
+        assert_eq!(backups.list().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,361 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Action, Engine, EngineSnapshot, Observation, OverrideKey};
+use crate::ime::ImeStatus;
+use crate::process::ProcessInfo;
+use crate::rules::EffectiveSettings;
+use crate::state::StatusMessage;
+
+/// One line of a trace file: an input the engine received, with what it decided where there
+/// is a decision. Replaying the inputs in order reproduces the engine state exactly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum TraceEvent {
+    /// First line of every trace.
+    Start {
+        engine: EngineSnapshot,
+    },
+    Observe {
+        /// Milliseconds since recording started.
+        at_ms: u64,
+        observation: RecordedObservation,
+        actions: Vec<Action>,
+    },
+    Switched {
+        at_ms: u64,
+        toggled: bool,
+        status: Option<StatusMessage>,
+    },
+    ManualOverride {
+        #[serde(default)]
+        at_ms: u64,
+        #[serde(flatten)]
+        key: OverrideKey,
+        enabled: bool,
+        /// Set for a time-limited override.
+        #[serde(default)]
+        duration_ms: Option<u64>,
+    },
+    /// The focus snapshot was replaced outside the monitor (the toggle command).
+    NoteFocus {
+        focus: Option<(String, ImeStatus)>,
+    },
+}
+
+/// The part of a [`ProcessInfo`] the engine looks at. Window titles, paths and command lines
+/// stay out of traces so they can be attached to bug reports as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedProcess {
+    pub pid: u32,
+    pub name: String,
+}
+
+/// An owned [`Observation`] without the clock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedObservation {
+    pub process: Option<RecordedProcess>,
+    pub settings: Option<EffectiveSettings>,
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    pub ime: ImeStatus,
+    pub cursor: Option<(i32, i32)>,
+    #[serde(default)]
+    pub keyboard_idle_ms: Option<u64>,
+    pub use_auto_to_en: bool,
+}
+
+impl RecordedObservation {
+    fn from_observation(observation: &Observation) -> Self {
+        Self {
+            process: observation.process.map(|process| RecordedProcess {
+                pid: process.pid,
+                name: process.name.clone(),
+            }),
+            settings: observation.settings,
+            rule_id: observation.rule_id.map(String::from),
+            ime: observation.ime,
+            cursor: observation.cursor,
+            keyboard_idle_ms: observation
+                .keyboard_idle
+                .map(|idle| idle.as_millis() as u64),
+            use_auto_to_en: observation.use_auto_to_en,
+        }
+    }
+
+    fn process(&self) -> Option<ProcessInfo> {
+        self.process.as_ref().map(|process| ProcessInfo {
+            pid: process.pid,
+            name: process.name.clone(),
+            ..ProcessInfo::default()
+        })
+    }
+
+    fn observation<'a>(&'a self, process: Option<&'a ProcessInfo>, at: Instant) -> Observation<'a> {
+        Observation {
+            process,
+            settings: self.settings,
+            rule_id: self.rule_id.as_deref(),
+            ime: self.ime,
+            cursor: self.cursor,
+            keyboard_idle: self.keyboard_idle_ms.map(Duration::from_millis),
+            use_auto_to_en: self.use_auto_to_en,
+            at,
+        }
+    }
+}
+
+/// Appends [`TraceEvent`]s to a JSON Lines file while recording is on.
+#[derive(Debug)]
+pub struct TraceRecorder {
+    path: PathBuf,
+    writer: LineWriter<File>,
+    started: Instant,
+}
+
+impl TraceRecorder {
+    /// Starts a new trace from the current `engine` state. Never overwrites an existing file.
+    pub fn create(path: &Path, engine: &Engine) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("trace 디렉터리를 만들 수 없습니다: {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .with_context(|| format!("trace 파일을 만들 수 없습니다: {}", path.display()))?;
+        let started = Instant::now();
+        let mut recorder = Self {
+            path: path.to_path_buf(),
+            writer: LineWriter::new(file),
+            started,
+        };
+        recorder.write(&TraceEvent::Start {
+            engine: engine.snapshot(started),
+        })?;
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn observe(&mut self, observation: &Observation, actions: &[Action]) {
+        self.record(&TraceEvent::Observe {
+            at_ms: self.elapsed_ms(observation.at),
+            observation: RecordedObservation::from_observation(observation),
+            actions: actions.to_vec(),
+        });
+    }
+
+    pub fn manual_override(
+        &mut self,
+        key: &OverrideKey,
+        enabled: bool,
+        at: Instant,
+        duration: Option<Duration>,
+    ) {
+        self.record(&TraceEvent::ManualOverride {
+            at_ms: self.elapsed_ms(at),
+            key: key.clone(),
+            enabled,
+            duration_ms: duration.map(|duration| duration.as_millis() as u64),
+        });
+    }
+
+    pub fn switched(&mut self, toggled: bool, at: Instant, status: Option<&StatusMessage>) {
+        self.record(&TraceEvent::Switched {
+            at_ms: self.elapsed_ms(at),
+            toggled,
+            status: status.cloned(),
+        });
+    }
+
+    /// Write failures are logged rather than returned; a broken trace must not stop switching.
+    pub fn record(&mut self, event: &TraceEvent) {
+        if let Err(err) = self.write(event) {
+            tracing::warn!(?err, path = %self.path.display(), "trace 기록 실패");
+        }
+    }
+
+    fn write(&mut self, event: &TraceEvent) -> Result<()> {
+        let line = serde_json::to_string(event).context("trace 직렬화 실패")?;
+        writeln!(self.writer, "{line}")?;
+        Ok(())
+    }
+
+    fn elapsed_ms(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.started).as_millis() as u64
+    }
+}
+
+/// Result of [`replay`]. An empty `mismatches` means the engine still decides exactly as it
+/// did when the trace was recorded.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayReport {
+    pub events: usize,
+    pub mismatches: Vec<ReplayMismatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayMismatch {
+    /// 1-based line in the trace file.
+    pub line: usize,
+    pub recorded: serde_json::Value,
+    pub replayed: serde_json::Value,
+}
+
+/// Feeds a recorded trace through a fresh [`Engine`] without touching the OS and compares
+/// every decision with the recorded one.
+pub fn replay(reader: impl BufRead) -> Result<ReplayReport> {
+    let mut engine = Engine::default();
+    let base = Instant::now();
+    let at = |at_ms: u64| base + Duration::from_millis(at_ms);
+    let mut report = ReplayReport {
+        events: 0,
+        mismatches: Vec::new(),
+    };
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.context("trace 파일을 읽을 수 없습니다")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event: TraceEvent = serde_json::from_str(&line)
+            .with_context(|| format!("trace {}번째 줄을 해석할 수 없습니다", index + 1))?;
+        report.events += 1;
+
+        let mismatch = match event {
+            TraceEvent::Start { engine: snapshot } => {
+                engine = Engine::from_snapshot(snapshot, base);
+                None
+            }
+            TraceEvent::Observe {
+                at_ms,
+                observation,
+                actions,
+            } => {
+                let process = observation.process();
+                let observation = observation.observation(process.as_ref(), at(at_ms));
+                let replayed = engine.observe(&observation);
+                (replayed != actions).then(|| (to_value(&actions), to_value(&replayed)))
+            }
+            TraceEvent::Switched {
+                at_ms,
+                toggled,
+                status,
+            } => {
+                let replayed = engine.switched(toggled, at(at_ms));
+                (replayed != status).then(|| (to_value(&status), to_value(&replayed)))
+            }
+            TraceEvent::ManualOverride {
+                at_ms,
+                key,
+                enabled,
+                duration_ms,
+            } => {
+                let duration = duration_ms.map(Duration::from_millis);
+                engine.set_manual_override(&key, enabled, at(at_ms), duration);
+                None
+            }
+            TraceEvent::NoteFocus { focus } => {
+                engine.note_focus(focus);
+                None
+            }
+        };
+        if let Some((recorded, replayed)) = mismatch {
+            report.mismatches.push(ReplayMismatch {
+                line: index + 1,
+                recorded,
+                replayed,
+            });
+        }
+    }
+    Ok(report)
+}
+
+fn to_value(value: &impl Serialize) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Traces of reported bugs; each must keep replaying to the decisions it recorded.
+    fn fixtures() -> Vec<PathBuf> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/traces");
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn recorded_traces_replay_unchanged() {
+        let paths = fixtures();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let file = std::io::BufReader::new(File::open(&path).unwrap());
+            let report = replay(file).unwrap();
+            assert!(report.events > 0, "{}", path.display());
+            assert!(report.mismatches.is_empty(), "{}: {:?}", path.display(), report.mismatches);
+        }
+    }
+
+    #[test]
+    fn only_the_process_name_is_recorded() {
+        let process = ProcessInfo {
+            pid: 4120,
+            name: "notepad.exe".into(),
+            title: "secret.txt - Notepad".into(),
+            path: Some(r"C:\Windows\notepad.exe".into()),
+            command_line: Some(r"notepad.exe C:\secret.txt".into()),
+            ..ProcessInfo::default()
+        };
+        let observation = Observation {
+            process: Some(&process),
+            settings: None,
+            rule_id: None,
+            ime: ImeStatus::English,
+            cursor: None,
+            keyboard_idle: None,
+            use_auto_to_en: true,
+            at: Instant::now(),
+        };
+
+        let recorded = RecordedObservation::from_observation(&observation);
+        let line = serde_json::to_string(&recorded).unwrap();
+        assert!(line.contains("notepad.exe"));
+        assert!(!line.contains("secret"));
+        assert!(!line.contains("Windows"));
+    }
+
+    #[test]
+    fn changed_decisions_are_reported() {
+        let path = &fixtures()[0];
+        let trace = fs::read_to_string(path).unwrap();
+        let edited = trace.replacen(r#""actions":[{"switch":"english"}]"#, r#""actions":[]"#, 1);
+        assert_ne!(trace, edited);
+
+        let report = replay(Cursor::new(edited)).unwrap();
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].line, 2);
+    }
+}
@@ -0,0 +1,166 @@
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueKind {
+    NotPositive,
+    BelowMinimum,
+    UnsupportedLanguage,
+    EmptyProcessName,
+    DuplicateRuleId,
+    InvalidPattern,
+    Migrated,
+    NewerSchema,
+    Unreadable,
+    RestoredFromBackup,
+    ResetToDefaults,
+}
+
+impl IssueKind {
+    /// Warnings replaced a value with a usable one; errors mean a rule or the saved settings
+    /// were dropped or cannot take effect.
+    pub fn severity(self) -> IssueSeverity {
+        match self {
+            IssueKind::Migrated => IssueSeverity::Info,
+            IssueKind::NotPositive
+            | IssueKind::BelowMinimum
+            | IssueKind::UnsupportedLanguage
+            | IssueKind::DuplicateRuleId
+            | IssueKind::NewerSchema
+            | IssueKind::RestoredFromBackup => IssueSeverity::Warning,
+            IssueKind::EmptyProcessName
+            | IssueKind::InvalidPattern
+            | IssueKind::Unreadable
+            | IssueKind::ResetToDefaults => IssueSeverity::Error,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            IssueKind::NotPositive => "0 이하의 값은 허용되지 않아 기본값으로 바꿨습니다",
+            IssueKind::BelowMinimum => "최솟값보다 작아 최솟값으로 올렸습니다",
+            IssueKind::UnsupportedLanguage => "지원하지 않는 언어라 기본 언어로 바꿨습니다",
+            IssueKind::EmptyProcessName => "조건(프로세스 이름, 경로, 창 제목)이 모두 비어 있는 규칙을 제거했습니다",
+            IssueKind::DuplicateRuleId => "중복되거나 비어 있는 규칙 ID를 새로 발급했습니다",
+            IssueKind::InvalidPattern => "패턴을 해석할 수 없어 이 규칙은 적용되지 않습니다",
+            IssueKind::Migrated => "이전 스키마 버전에서 변환했습니다",
+            IssueKind::NewerSchema => "더 최신 버전에서 저장된 파일이라 덮어쓰지 않도록 읽기 전용으로 불러왔습니다",
+            IssueKind::Unreadable => "파일을 읽거나 해석할 수 없습니다",
+            IssueKind::RestoredFromBackup => "손상된 파일 대신 백업에서 복구했습니다",
+            IssueKind::ResetToDefaults => "손상된 파일을 대체할 백업이 없어 기본값을 사용했습니다",
+        }
+    }
+}
+
+/// A value that was rejected while loading or editing the config, and what replaced it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    pub field: String,
+    pub kind: IssueKind,
+    pub severity: IssueSeverity,
+    pub value: Value,
+    pub applied: Value,
+}
+
+impl ConfigIssue {
+    pub fn new(
+        field: impl Into<String>,
+        kind: IssueKind,
+        value: impl Into<Value>,
+        applied: impl Into<Value>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            kind,
+            severity: kind.severity(),
+            value: value.into(),
+            applied: applied.into(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let severity = match self.severity {
+            IssueSeverity::Info => "INFO",
+            IssueSeverity::Warning => "WARN",
+            IssueSeverity::Error => "ERROR",
+        };
+        format!(
+            "[{severity}] {}: {} → {} ({})",
+            self.field,
+            self.value,
+            self.applied,
+            self.kind.description()
+        )
+    }
+}
+
+/// Rejects zero, negative, NaN and infinite values.
+pub fn is_positive_finite(value: f32) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+pub fn has_errors(issues: &[ConfigIssue]) -> bool {
+    issues
+        .iter()
+        .any(|issue| issue.severity == IssueSeverity::Error)
+}
+
+/// Replaces any earlier issue for the same field, so repeated edits keep one entry per field.
+pub fn upsert_issue(issues: &mut Vec<ConfigIssue>, issue: ConfigIssue) {
+    issues.retain(|existing| existing.field != issue.field);
+    issues.push(issue);
+}
+
+/// Adds a fresh report to `issues`. Earlier issues for the fields it covers are replaced;
+/// the rest (e.g. a recovery on load or a clamped edit) are kept.
+pub fn merge_issues(issues: &mut Vec<ConfigIssue>, report: Vec<ConfigIssue>) {
+    issues.retain(|existing| !report.iter().any(|issue| issue.field == existing.field));
+    issues.extend(report);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merging_keeps_issues_for_other_fields() {
+        let mut issues = vec![
+            ConfigIssue::new("config.json", IssueKind::RestoredFromBackup, "", Value::Null),
+            ConfigIssue::new("mouse_sensitivity", IssueKind::BelowMinimum, 1.0, 10.0),
+        ];
+        merge_issues(
+            &mut issues,
+            vec![
+                ConfigIssue::new("mouse_sensitivity", IssueKind::NotPositive, 0.0, 50.0),
+                ConfigIssue::new("resume_policies", IssueKind::NotPositive, 1, Value::Null),
+                ConfigIssue::new("resume_policies", IssueKind::NotPositive, 2, Value::Null),
+            ],
+        );
+
+        let kinds: Vec<_> = issues.iter().map(|issue| (issue.field.as_str(), issue.kind)).collect();
+        assert_eq!(
+            kinds,
+            [
+                ("config.json", IssueKind::RestoredFromBackup),
+                ("mouse_sensitivity", IssueKind::NotPositive),
+                ("resume_policies", IssueKind::NotPositive),
+                ("resume_policies", IssueKind::NotPositive),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaced_values_share_one_severity() {
+        assert_eq!(IssueKind::NotPositive.severity(), IssueKind::BelowMinimum.severity());
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+
+use crate::config::ConfigManager;
+use crate::state::{ExternalConfigChange, SharedAppState};
+
+/// How often the watcher thread checks for shutdown while no events arrive.
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+/// Editors and deployment tools often write in several steps; wait until they settle.
+const SETTLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Watches the config directory and feeds edits of `config.json` made by other programs back
+/// into [`AppState`]. Watching the directory rather than the file also catches editors that
+/// save by writing a temp file and renaming it over `config.json`.
+///
+/// [`AppState`]: crate::state::AppState
+pub struct ConfigWatcher {
+    shutdown: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl ConfigWatcher {
+    pub fn start(app: AppHandle, state: SharedAppState, config_manager: Arc<ConfigManager>) -> Self {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let thread_shutdown = shutdown.clone();
+
+        let handle = thread::spawn(move || {
+            let (tx, rx) = mpsc::channel();
+            let dir = config_manager.location().dir.clone();
+            // Dropping the watcher at the end of the thread stops the notifications.
+            let _watcher = match watch_dir(&dir, tx) {
+                Ok(watcher) => watcher,
+                Err(err) => {
+                    tracing::warn!(?err, dir = %dir.display(), "config 디렉터리를 감시할 수 없습니다");
+                    return;
+                }
+            };
+            watch_loop(app, state, config_manager, rx, thread_shutdown);
+        });
+
+        Self {
+            shutdown,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn watch_dir(dir: &Path, tx: mpsc::Sender<notify::Result<Event>>) -> Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(tx).context("파일 감시기 생성 실패")?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .context("config 디렉터리 감시 시작 실패")?;
+    Ok(watcher)
+}
+
+fn watch_loop(
+    app: AppHandle,
+    state: SharedAppState,
+    config_manager: Arc<ConfigManager>,
+    events: Receiver<notify::Result<Event>>,
+    shutdown: Arc<AtomicBool>,
+) {
+    let path = config_manager.config_path().to_path_buf();
+
+    while !shutdown.load(Ordering::Relaxed) {
+        match events.recv_timeout(SHUTDOWN_CHECK_INTERVAL) {
+            Ok(Ok(event)) if touches(&event, &path) => {}
+            Ok(Ok(_)) | Err(RecvTimeoutError::Timeout) => continue,
+            Ok(Err(err)) => {
+                tracing::warn!(?err, "config 디렉터리 감시 오류");
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        // Coalesce the burst of events a single save produces.
+        thread::sleep(SETTLE_DELAY);
+        while events.try_recv().is_ok() {}
+        if !path.exists() {
+            continue;
+        }
+
+        let (config, issues) = match config_manager.reload() {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                // Deployment tools may still be writing; the next change will be picked up.
+                tracing::warn!(?err, path = %path.display(), "변경된 config를 읽을 수 없습니다");
+                continue;
+            }
+        };
+
+        let (change, view_model) = {
+            let mut guard = state.lock();
+            let change = guard.apply_external_config(config, issues);
+            (change, guard.to_view_model())
+        };
+
+        match change {
+            ExternalConfigChange::Unchanged => {}
+            ExternalConfigChange::Applied => {
+                tracing::info!("외부에서 변경된 config를 적용했습니다");
+                crate::apply_tray_language(&app, &view_model.draft_config.language);
+                let _ = app.emit("config-reloaded", view_model);
+            }
+            ExternalConfigChange::Conflict => {
+                tracing::warn!("저장하지 않은 변경 사항이 있어 외부 config 변경과 충돌합니다");
+                let _ = app.emit("config-conflict", view_model);
+            }
+        }
+    }
+}
+
+/// Whether `event` may have changed the file at `path`, including a rename onto it.
+fn touches(event: &Event, path: &Path) -> bool {
+    let name = path.file_name();
+    event
+        .paths
+        .iter()
+        .any(|changed| changed.file_name() == name)
+}
@@ -2,7 +2,7 @@ import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { openUrl } from "@tauri-apps/plugin-opener";
 import { listen } from "@tauri-apps/api/event";
-import { ArrowUpCircle, Coffee, Languages, Moon, Plus, RefreshCw, Settings, SunMedium, Trash2, Undo2 } from "lucide-react";
+import { ArrowUpCircle, ChevronDown, Coffee, Languages, Moon, Plus, RefreshCw, Settings, SunMedium, Trash2, Undo2 } from "lucide-react";
 import { toast } from "sonner";
 import { LANGUAGE_LABELS, languageOptions, useI18n, useLanguage } from "@/components/language-provider";
 import { type SupportedLanguage, type TranslationValues } from "@/lib/i18n";
@@ -27,16 +27,93 @@ type ProcessInfo = {
   pid: number;
   name: string;
   title: string;
+  path?: string | null;
+  startTime?: number | null;
+  memoryBytes?: number | null;
+  cpuUsage?: number | null;
+  displayName?: string | null;
 };
 
+type ProcessSortKey = "name" | "displayName" | "pid" | "startTime" | "memory" | "cpu";
+
+const PROCESS_SORT_KEYS: ProcessSortKey[] = ["name", "displayName", "pid", "startTime", "memory", "cpu"];
+
+type ProcessSort = {
+  sortBy: ProcessSortKey;
+  descending: boolean;
+};
+
+function formatMemory(bytes: number) {
+  const megabytes = bytes / (1024 * 1024);
+  return megabytes >= 1024 ? `${(megabytes / 1024).toFixed(1)} GB` : `${Math.round(megabytes)} MB`;
+}
+
+type WindowInfo = {
+  pid: number;
+  title: string;
+  visible: boolean;
+};
+
+type AppEntry = ProcessInfo & {
+  windows: WindowInfo[];
+  instanceCount: number;
+};
+
+type ProcessListUpdate =
+  | { kind: "full"; seq: number; processes: AppEntry[] }
+  | {
+      kind: "diff";
+      seq: number;
+      baseSeq: number;
+      added: AppEntry[];
+      changed: AppEntry[];
+      removed: string[];
+      order?: string[] | null;
+      metrics: ProcessMetrics[];
+    };
+
+type ProcessMetrics = {
+  key: string;
+  memoryBytes?: number | null;
+  cpuUsage?: number | null;
+};
+
+const processKey = (proc: ProcessInfo) => (proc.path ?? proc.name).toLowerCase();
+
+function applyProcessDiff(current: AppEntry[], diff: Extract<ProcessListUpdate, { kind: "diff" }>): AppEntry[] {
+  const removed = new Set(diff.removed);
+  const byKey = new Map(
+    current.filter((proc) => !removed.has(processKey(proc))).map((proc) => [processKey(proc), proc]),
+  );
+  [...diff.added, ...diff.changed].forEach((proc) => byKey.set(processKey(proc), proc));
+  diff.metrics.forEach(({ key, memoryBytes, cpuUsage }) => {
+    const proc = byKey.get(key);
+    if (proc) {
+      byKey.set(key, { ...proc, memoryBytes, cpuUsage });
+    }
+  });
+  if (!diff.order) {
+    return [...byKey.values()];
+  }
+  return diff.order.map((key) => byKey.get(key)).filter((proc): proc is AppEntry => proc !== undefined);
+}
+
 type FocusSnapshot = {
   process?: ProcessInfo;
+  matchedRule?: { id: string } | null;
   imeStatus: ImeStatus;
   manualOverride: boolean;
+  manualOverrideRemainingSecs?: number | null;
   updatedAt?: string | null;
   lastUpdated?: string;
 };
 
+function formatRemaining(totalSecs: number) {
+  const minutes = Math.floor(totalSecs / 60);
+  const seconds = totalSecs % 60;
+  return `${minutes}:${String(seconds).padStart(2, "0")}`;
+}
+
 type StatusMessage = {
   key: string;
   values?: TranslationValues;
@@ -47,6 +124,7 @@ type AppConfig = {
   useAutoToEn: boolean;
   useMouseMoveEvent: boolean;
   detectIntervalSecs: number;
+  maxDetectIntervalSecs: number;
   mouseSensitivity: number;
   startWithWindows: boolean;
   language: SupportedLanguage;
@@ -55,8 +133,10 @@ type AppConfig = {
 type AppViewModel = {
   savedConfig: AppConfig;
   draftConfig: AppConfig;
-  availableProcesses: ProcessInfo[];
+  availableProcesses: AppEntry[];
+  processListSeq: number;
   focus?: FocusSnapshot | null;
+  effectiveDetectIntervalSecs: number;
   hasUnsavedChanges: boolean;
   statusMessage?: StatusMessage | null;
 };
@@ -70,6 +150,8 @@ const LANGUAGE_ICONS: Record<SupportedLanguage, string> = {
 
 const DEFAULT_DETECT_INTERVAL = 0.5;
 const DEFAULT_MOUSE_SENSITIVITY = 100;
+// Minutes offered for a time-limited pause; "none" pauses until a resume policy ends it.
+const MANUAL_OVERRIDE_MINUTES = [5, 15, 30, 60];
 
 function App() {
   const { t } = useI18n();
@@ -77,9 +159,16 @@ function App() {
   const [theme, setTheme] = useState<"light" | "dark">("light");
   const [savedConfig, setSavedConfig] = useState<AppConfig | null>(null);
   const [draftConfig, setDraftConfig] = useState<AppConfig | null>(null);
-  const [availableProcesses, setAvailableProcesses] = useState<ProcessInfo[]>([]);
+  const [availableProcesses, setAvailableProcesses] = useState<AppEntry[]>([]);
+  const processSeqRef = useRef(0);
+  const resyncingProcessesRef = useRef(false);
+  const [expandedProcesses, setExpandedProcesses] = useState<Set<string>>(new Set());
   const [processQuery, setProcessQuery] = useState("");
+  const [processSort, setProcessSort] = useState<ProcessSort>({ sortBy: "name", descending: false });
   const [focusSnapshot, setFocusSnapshot] = useState<FocusSnapshot | null>(null);
+  const [effectiveDetectInterval, setEffectiveDetectInterval] = useState<number | null>(null);
+  const [manualOverrideMinutes, setManualOverrideMinutes] = useState("none");
+  const [manualOverrideRemaining, setManualOverrideRemaining] = useState<number | null>(null);
   const [loading, setLoading] = useState(true);
   const [prioritizeSelected, setPrioritizeSelected] = useState(true);
   const autosaveTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
@@ -103,6 +192,8 @@ function App() {
       setSavedConfig(view.savedConfig);
       setDraftConfig(view.draftConfig);
       setAvailableProcesses(view.availableProcesses);
+      processSeqRef.current = view.processListSeq;
+      setEffectiveDetectInterval(view.effectiveDetectIntervalSecs);
       if (view.statusMessage) {
         toast.info(t(view.statusMessage.key, view.statusMessage.values));
       }
@@ -224,7 +315,13 @@ function App() {
     if (!draftConfig) return;
     try {
       setDraftConfig((prev) =>
-        prev ? { ...prev, detectIntervalSecs: DEFAULT_DETECT_INTERVAL } : prev,
+        prev
+          ? {
+              ...prev,
+              detectIntervalSecs: DEFAULT_DETECT_INTERVAL,
+              maxDetectIntervalSecs: Math.max(prev.maxDetectIntervalSecs, DEFAULT_DETECT_INTERVAL),
+            }
+          : prev,
       );
       await invokeAndSync("set_detect_interval", { seconds: DEFAULT_DETECT_INTERVAL });
       await scheduleAutoSave();
@@ -233,6 +330,22 @@ function App() {
     }
   }, [draftConfig, handleError, invokeAndSync, scheduleAutoSave]);
 
+  const resetMaxDetectInterval = useCallback(async () => {
+    if (!draftConfig) return;
+    try {
+      setDraftConfig((prev) =>
+        prev
+          ? { ...prev, maxDetectIntervalSecs: prev.detectIntervalSecs }
+          : prev,
+      );
+      // The default polls at a fixed rate: the maximum equals the detection interval.
+      await invokeAndSync("set_max_detect_interval", { seconds: draftConfig.detectIntervalSecs });
+      await scheduleAutoSave();
+    } catch (err) {
+      handleError(err);
+    }
+  }, [draftConfig, handleError, invokeAndSync, scheduleAutoSave]);
+
   const resetMouseSensitivity = useCallback(async () => {
     if (!draftConfig) return;
     try {
@@ -289,13 +402,38 @@ function App() {
         }
       });
 
-      const processUnlisten = await listen<ProcessInfo[]>("processes-updated", (event) => {
-        if (Array.isArray(event.payload)) {
-          setAvailableProcesses(event.payload);
+      const processUnlisten = await listen<ProcessListUpdate>("processes-updated", (event) => {
+        const update = event.payload;
+        // Already covered by a newer list, e.g. one that came with a view model.
+        if (update.seq <= processSeqRef.current) return;
+        if (update.kind === "full") {
+          processSeqRef.current = update.seq;
+          setAvailableProcesses(update.processes);
+        } else if (update.baseSeq === processSeqRef.current) {
+          processSeqRef.current = update.seq;
+          setAvailableProcesses((current) => applyProcessDiff(current, update));
+        } else if (!resyncingProcessesRef.current) {
+          // Missed an update; the view model carries the full list.
+          resyncingProcessesRef.current = true;
+          invoke<AppViewModel>("refresh_processes")
+            .then((view) => {
+              if (view.processListSeq >= processSeqRef.current) {
+                processSeqRef.current = view.processListSeq;
+                setAvailableProcesses(view.availableProcesses);
+              }
+            })
+            .catch((err) => console.error(err))
+            .finally(() => {
+              resyncingProcessesRef.current = false;
+            });
         }
       });
 
-      unsubscribes = [focusUnlisten, statusUnlisten, processUnlisten];
+      const intervalUnlisten = await listen<number>("detect-interval-changed", (event) => {
+        setEffectiveDetectInterval(event.payload);
+      });
+
+      unsubscribes = [focusUnlisten, statusUnlisten, processUnlisten, intervalUnlisten];
     };
 
     listenAll();
@@ -305,24 +443,35 @@ function App() {
     };
   }, []);
 
+  // The backend filters and sorts the list; typing is debounced so each keystroke does not
+  // re-enumerate the windows.
+  useEffect(() => {
+    if (loading) return;
+    const timer = setTimeout(() => {
+      const filter = processQuery.trim();
+      invoke<AppViewModel>("refresh_processes", { query: { filter: filter || null, ...processSort } })
+        .then((view) => {
+          processSeqRef.current = view.processListSeq;
+          setAvailableProcesses(view.availableProcesses);
+        })
+        .catch((err) => handleError(err));
+    }, 250);
+    return () => clearTimeout(timer);
+  }, [loading, processQuery, processSort, handleError]);
+
+  // Picked rules match process names case-insensitively.
+  const selectedSet = useMemo(
+    () => new Set((draftConfig?.selectedProcesses ?? []).map((name) => name.toLowerCase())),
+    [draftConfig?.selectedProcesses],
+  );
+
   const filteredProcesses = useMemo(() => {
-    const selectedSet = new Set(draftConfig?.selectedProcesses ?? []);
-    if (!processQuery.trim()) {
-      if (!prioritizeSelected) {
-        return availableProcesses;
-      }
-      return [...availableProcesses].sort(
-        (a, b) => Number(selectedSet.has(b.name)) - Number(selectedSet.has(a.name)),
-      );
-    }
-    const filtered = availableProcesses.filter((proc) =>
-      `${proc.name} ${proc.title}`.toLowerCase().includes(processQuery.trim().toLowerCase()),
-    );
     if (!prioritizeSelected) {
-      return filtered;
+      return availableProcesses;
     }
-    return [...filtered].sort((a, b) => Number(selectedSet.has(b.name)) - Number(selectedSet.has(a.name)));
-  }, [availableProcesses, processQuery, prioritizeSelected, draftConfig?.selectedProcesses]);
+    const isSelected = (name: string) => Number(selectedSet.has(name.toLowerCase()));
+    return [...availableProcesses].sort((a, b) => isSelected(b.name) - isSelected(a.name));
+  }, [availableProcesses, prioritizeSelected, selectedSet]);
 
   const handleAddProcess = async (name: string) => {
     try {
@@ -334,6 +483,28 @@ function App() {
     }
   };
 
+  const toggleProcessExpanded = (key: string) => {
+    setExpandedProcesses((current) => {
+      const next = new Set(current);
+      if (!next.delete(key)) {
+        next.add(key);
+      }
+      return next;
+    });
+  };
+
+  const handleAddTitleRule = async (name: string, title: string) => {
+    try {
+      await invokeAndSync("add_process_rule", {
+        rule: { processName: name, matchKind: "literal", titlePattern: title, titleMatchKind: "substring" },
+      });
+      toast.success(t("toast.process.titleRuleAdded", { name, title }));
+      await scheduleAutoSave();
+    } catch (err) {
+      handleError(err);
+    }
+  };
+
   const handleRemoveProcess = async (name: string) => {
     try {
       await invokeAndSync("remove_selected_process", { name });
@@ -367,18 +538,42 @@ function App() {
   };
 
   const toggleManualOverride = async (enabled: boolean) => {
-    if (!focusSnapshot?.process) return;
+    if (!focusSnapshot?.process || !focusSnapshot.matchedRule) return;
+    const minutes = manualOverrideMinutes === "none" ? null : Number(manualOverrideMinutes);
     try {
       await invokeAndSync("set_manual_override", {
+        ruleId: focusSnapshot.matchedRule.id,
         processName: focusSnapshot.process.name,
         enabled,
+        durationSecs: enabled && minutes ? minutes * 60 : null,
       });
-      toast.info(enabled ? t("toast.manual.enabled") : t("toast.manual.disabled"));
+      if (!enabled) {
+        toast.info(t("toast.manual.disabled"));
+      } else if (minutes) {
+        toast.info(t("toast.manual.enabledFor", { minutes }));
+      } else {
+        toast.info(t("toast.manual.enabled"));
+      }
     } catch (err) {
       handleError(err);
     }
   };
 
+  // The backend reports the remaining time only when the focus is polled, which slows down
+  // while nothing changes, so the countdown ticks here between reports.
+  const reportedRemainingSecs = focusSnapshot?.manualOverride ? focusSnapshot.manualOverrideRemainingSecs : null;
+  useEffect(() => {
+    if (reportedRemainingSecs == null) {
+      setManualOverrideRemaining(null);
+      return;
+    }
+    const deadline = Date.now() + reportedRemainingSecs * 1000;
+    const update = () => setManualOverrideRemaining(Math.max(0, Math.ceil((deadline - Date.now()) / 1000)));
+    update();
+    const timer = setInterval(update, 1000);
+    return () => clearInterval(timer);
+  }, [reportedRemainingSecs, focusSnapshot?.updatedAt]);
+
   const themeIcon = theme === "dark" ? <SunMedium className="size-4" /> : <Moon className="size-4" />;
 
   const imeLabel = useMemo(() => {
@@ -561,7 +756,15 @@ function App() {
                           max={2}
                           step={0.1}
                           onValueChange={([value]) => {
-                            setDraftConfig((prev) => (prev ? { ...prev, detectIntervalSecs: value } : prev));
+                            setDraftConfig((prev) =>
+                              prev
+                                ? {
+                                    ...prev,
+                                    detectIntervalSecs: value,
+                                    maxDetectIntervalSecs: Math.max(prev.maxDetectIntervalSecs, value),
+                                  }
+                                : prev,
+                            );
                           }}
                           onValueCommit={async ([value]) => {
                             try {
@@ -572,6 +775,58 @@ function App() {
                             }
                           }}
                         />
+                        <div className="flex items-center justify-between text-sm">
+                          <div>
+                            <p className="font-medium flex items-center gap-2">
+                              {t("settings.maxDetectInterval.title")}
+                              <button
+                                type="button"
+                                onClick={resetMaxDetectInterval}
+                                className="text-muted-foreground transition hover:text-foreground disabled:cursor-not-allowed disabled:opacity-50"
+                                aria-label={t("settings.maxDetectInterval.reset", {
+                                  seconds: draftConfig.detectIntervalSecs.toFixed(1),
+                                })}
+                                disabled={draftConfig.maxDetectIntervalSecs === draftConfig.detectIntervalSecs}
+                              >
+                                <Undo2 className="size-4" />
+                              </button>
+                            </p>
+                            <p className="text-xs text-muted-foreground">
+                              {t("settings.maxDetectInterval.description", {
+                                seconds: draftConfig.maxDetectIntervalSecs.toFixed(1),
+                              })}
+                            </p>
+                            {effectiveDetectInterval !== null && (
+                              <p className="text-xs text-muted-foreground">
+                                {t("settings.maxDetectInterval.current", {
+                                  seconds: effectiveDetectInterval.toFixed(1),
+                                })}
+                              </p>
+                            )}
+                          </div>
+                          <Badge variant="outline" className="text-muted-foreground">
+                            {t("settings.maxDetectInterval.badge", {
+                              seconds: draftConfig.maxDetectIntervalSecs.toFixed(1),
+                            })}
+                          </Badge>
+                        </div>
+                        <Slider
+                          value={[draftConfig.maxDetectIntervalSecs]}
+                          min={draftConfig.detectIntervalSecs}
+                          max={10}
+                          step={0.1}
+                          onValueChange={([value]) => {
+                            setDraftConfig((prev) => (prev ? { ...prev, maxDetectIntervalSecs: value } : prev));
+                          }}
+                          onValueCommit={async ([value]) => {
+                            try {
+                              await invokeAndSync("set_max_detect_interval", { seconds: value });
+                              await scheduleAutoSave();
+                            } catch (err) {
+                              handleError(err);
+                            }
+                          }}
+                        />
                         <div className="flex items-center justify-between text-sm">
                           <div>
                             <p className="font-medium flex items-center gap-2">
@@ -692,11 +947,29 @@ function App() {
                       <Switch
                         id="manual-override"
                         checked={focusSnapshot.manualOverride}
+                        disabled={!focusSnapshot.matchedRule}
                         onCheckedChange={(checked) => toggleManualOverride(Boolean(checked))}
                       />
                       <Label htmlFor="manual-override" className="text-sm font-medium">
                         {t("focus.manualToggle")}
                       </Label>
+                      <Select
+                        value={manualOverrideMinutes}
+                        onValueChange={setManualOverrideMinutes}
+                        disabled={!focusSnapshot.matchedRule || focusSnapshot.manualOverride}
+                      >
+                        <SelectTrigger className="h-8 w-[130px]" aria-label={t("focus.duration.label")}>
+                          <SelectValue />
+                        </SelectTrigger>
+                        <SelectContent>
+                          <SelectItem value="none">{t("focus.duration.untilResumed")}</SelectItem>
+                          {MANUAL_OVERRIDE_MINUTES.map((minutes) => (
+                            <SelectItem key={minutes} value={String(minutes)}>
+                              {t("focus.duration.minutes", { count: minutes })}
+                            </SelectItem>
+                          ))}
+                        </SelectContent>
+                      </Select>
                     </div>
                     <div className="flex flex-wrap items-center gap-2">
                       <Badge variant="outline" className={`border ${imeLabel.tone}`}>
@@ -705,6 +978,10 @@ function App() {
                       {focusSnapshot.manualOverride ? (
                         <Badge variant="outline" className="border-amber-300 bg-amber-500/10 text-amber-600">
                           {t("focus.manualBadge")}
+                          {manualOverrideRemaining != null &&
+                            ` · ${t("focus.manualRemaining", {
+                              time: formatRemaining(manualOverrideRemaining),
+                            })}`}
                         </Badge>
                       ) : (
                         <Badge variant="outline" className="border-emerald-300 bg-emerald-500/10 text-emerald-600">
@@ -734,6 +1011,36 @@ function App() {
                     </Button>
                   </PopoverTrigger>
                   <PopoverContent className="w-64 space-y-3" align="end" sideOffset={8}>
+                    <div className="space-y-2">
+                      <p className="text-sm font-medium">{t("process.settings.sortBy")}</p>
+                      <Select
+                        value={processSort.sortBy}
+                        onValueChange={(value) =>
+                          setProcessSort((current) => ({ ...current, sortBy: value as ProcessSortKey }))
+                        }
+                      >
+                        <SelectTrigger>
+                          <SelectValue />
+                        </SelectTrigger>
+                        <SelectContent>
+                          {PROCESS_SORT_KEYS.map((key) => (
+                            <SelectItem key={key} value={key}>
+                              {t(`process.sort.${key}`)}
+                            </SelectItem>
+                          ))}
+                        </SelectContent>
+                      </Select>
+                    </div>
+                    <div className="flex items-center justify-between gap-3">
+                      <p className="text-sm font-medium">{t("process.settings.descending")}</p>
+                      <Switch
+                        checked={processSort.descending}
+                        onCheckedChange={(checked) =>
+                          setProcessSort((current) => ({ ...current, descending: Boolean(checked) }))
+                        }
+                      />
+                    </div>
+                    <Separator />
                     <div className="flex items-center justify-between gap-3">
                       <div className="space-y-1">
                         <p className="text-sm font-medium">{t("process.settings.prioritizeSelected")}</p>
@@ -762,41 +1069,114 @@ function App() {
               </div>
               <ScrollArea className="flex-1 min-h-0 rounded-lg border bg-muted/30 p-3">
                 <div className="space-y-3">
-                  {filteredProcesses.map((process) => (
-                    <div
-                      key={`${process.pid}-${process.name}`}
-                      className="flex items-center justify-between gap-3 rounded-lg border bg-background/60 p-3 shadow-xs transition hover:border-primary/40"
-                    >
-                      <div className="min-w-0 flex-1">
-                        <p className="text-sm font-semibold">{process.name}</p>
-                        <p className="text-xs text-muted-foreground line-clamp-1">{process.title}</p>
+                  {filteredProcesses.map((process) => {
+                    const key = processKey(process);
+                    const expanded = expandedProcesses.has(key);
+                    return (
+                      <div
+                        key={key}
+                        className="rounded-lg border bg-background/60 p-3 shadow-xs transition hover:border-primary/40"
+                      >
+                        <div className="flex items-center justify-between gap-3">
+                          <div className="min-w-0 flex-1">
+                            <p className="text-sm font-semibold">
+                              {process.name}
+                              {process.displayName ? (
+                                <span className="ml-2 font-normal text-muted-foreground">{process.displayName}</span>
+                              ) : null}
+                            </p>
+                            <p className="text-xs text-muted-foreground line-clamp-1">{process.title}</p>
+                            {process.path ? (
+                              <p className="text-xs text-muted-foreground/80 truncate" title={process.path}>
+                                {process.path}
+                              </p>
+                            ) : null}
+                          </div>
+                          <div className="flex shrink-0 flex-col items-end gap-1">
+                            <Badge variant="outline" className="text-muted-foreground">
+                              {process.instanceCount > 1
+                                ? t("process.instances", { count: process.instanceCount })
+                                : t("common.pidLabel", { pid: process.pid })}
+                            </Badge>
+                            {process.memoryBytes != null || process.cpuUsage != null ? (
+                              <p className="text-xs text-muted-foreground">
+                                {[
+                                  process.memoryBytes != null ? formatMemory(process.memoryBytes) : null,
+                                  process.cpuUsage != null ? `${process.cpuUsage.toFixed(1)}%` : null,
+                                ]
+                                  .filter(Boolean)
+                                  .join(" · ")}
+                              </p>
+                            ) : null}
+                          </div>
+                          {selectedSet.has(process.name.toLowerCase()) ? (
+                            <Button
+                              variant="outline"
+                              size="sm"
+                              className="shrink-0"
+                              onClick={() => handleRemoveProcess(process.name)}
+                            >
+                              <Trash2 className="size-4" />
+                              {t("process.action.remove")}
+                            </Button>
+                          ) : (
+                            <Button
+                              variant="outline"
+                              size="sm"
+                              className="shrink-0"
+                              onClick={() => handleAddProcess(process.name)}
+                            >
+                              <Plus className="size-4" />
+                              {t("process.action.add")}
+                            </Button>
+                          )}
+                        </div>
+                        {process.windows.length > 0 ? (
+                          <button
+                            type="button"
+                            className="mt-2 flex items-center gap-1 text-xs text-muted-foreground hover:text-foreground"
+                            onClick={() => toggleProcessExpanded(key)}
+                          >
+                            <ChevronDown className={`size-3 transition ${expanded ? "rotate-180" : ""}`} />
+                            {t("process.windows", { count: process.windows.length })}
+                          </button>
+                        ) : null}
+                        {expanded ? (
+                          <ul className="mt-2 space-y-1 border-l pl-3">
+                            {process.windows.map((window, index) => (
+                              <li key={`${window.pid}-${index}`} className="flex items-center gap-2 text-xs">
+                                <span
+                                  className={`min-w-0 flex-1 truncate ${window.visible ? "" : "text-muted-foreground"}`}
+                                  title={window.title}
+                                >
+                                  {window.title}
+                                </span>
+                                {!window.visible ? (
+                                  <Badge variant="outline" className="text-muted-foreground">
+                                    {t("process.window.hidden")}
+                                  </Badge>
+                                ) : null}
+                                {process.instanceCount > 1 ? (
+                                  <span className="shrink-0 text-muted-foreground">
+                                    {t("common.pidLabel", { pid: window.pid })}
+                                  </span>
+                                ) : null}
+                                <Button
+                                  variant="ghost"
+                                  size="sm"
+                                  className="h-7 shrink-0 px-2"
+                                  onClick={() => handleAddTitleRule(process.name, window.title)}
+                                >
+                                  <Plus className="size-3" />
+                                  {t("process.action.ruleFromTitle")}
+                                </Button>
+                              </li>
+                            ))}
+                          </ul>
+                        ) : null}
                       </div>
-                      <Badge variant="outline" className="text-muted-foreground shrink-0">
-                        {t("common.pidLabel", { pid: process.pid })}
-                      </Badge>
-                      {draftConfig.selectedProcesses.includes(process.name) ? (
-                        <Button
-                          variant="outline"
-                          size="sm"
-                          className="shrink-0"
-                          onClick={() => handleRemoveProcess(process.name)}
-                        >
-                          <Trash2 className="size-4" />
-                          {t("process.action.remove")}
-                        </Button>
-                      ) : (
-                        <Button
-                          variant="outline"
-                          size="sm"
-                          className="shrink-0"
-                          onClick={() => handleAddProcess(process.name)}
-                        >
-                          <Plus className="size-4" />
-                          {t("process.action.add")}
-                        </Button>
-                      )}
-                    </div>
-                  ))}
+                    );
+                  })}
                   {filteredProcesses.length === 0 ? (
                     <div className="rounded-lg border border-dashed bg-background/80 p-6 text-center text-muted-foreground">
                       {t("process.empty")}